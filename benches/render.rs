@@ -0,0 +1,75 @@
+//! Compares the single pass renderer against the old
+//! way of doing things, which was calling `String::replace`
+//! over the whole message for every key found in it.
+//!
+//! Run with `cargo bench`
+use paris::formatter::{colorize_string, write_string};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Roughly what the formatter did before, kept
+/// here so there's something to compare against
+fn replace_per_key(input: &str) -> String {
+    let mut output = input.to_string();
+    let mut rest = input;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+
+        let end = match rest.find('>') {
+            Some(end) => end + 1,
+            None => break,
+        };
+
+        let key = &rest[..end];
+        output = output.replace(key, &colorize_string(key));
+        rest = &rest[end..];
+    }
+
+    output
+}
+
+fn single_pass(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    write_string(input, true, &mut output).unwrap();
+
+    output
+}
+
+fn time<F: FnMut()>(iterations: u32, mut f: F) -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..iterations {
+        f();
+    }
+
+    start.elapsed() / iterations
+}
+
+fn main() {
+    let chunk = "<red>some</> <bold>tagged</> text <on-blue><white>here</> <tick> ";
+
+    println!(
+        "{:>8} {:>16} {:>16}",
+        "chunks", "replace (µs)", "single (µs)"
+    );
+
+    for &chunks in &[1, 10, 100, 1000] {
+        let input = chunk.repeat(chunks);
+        let iterations = 10_000 / chunks as u32 + 10;
+
+        let old = time(iterations, || {
+            black_box(replace_per_key(black_box(&input)));
+        });
+        let new = time(iterations, || {
+            black_box(single_pass(black_box(&input)));
+        });
+
+        println!(
+            "{:>8} {:>16.2} {:>16.2}",
+            chunks,
+            old.as_secs_f64() * 1e6,
+            new.as_secs_f64() * 1e6
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Something went wrong while expanding,
+/// turns into a `compile_error!` pointing at `span`
+pub struct Error {
+    message: String,
+    span: Span,
+}
+
+impl Error {
+    pub fn new<T: Into<String>>(message: T, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn into_compile_error(self) -> TokenStream {
+        let mut message = Literal::string(&self.message);
+        message.set_span(self.span);
+
+        let mut bang = Punct::new('!', Spacing::Alone);
+        bang.set_span(self.span);
+
+        let mut args = Group::new(Delimiter::Parenthesis, TokenTree::from(message).into());
+        args.set_span(self.span);
+
+        vec![
+            TokenTree::from(Ident::new("compile_error", self.span)),
+            bang.into(),
+            args.into(),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
@@ -0,0 +1,544 @@
+use crate::error::Error;
+use crate::format::{self, Argument, Placeholder, Segment};
+use crate::literal;
+use crate::tags::{self, Tag};
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// A single macro argument, `expr` or `name = expr`
+struct Arg {
+    name: Option<Ident>,
+    expr: TokenStream,
+    span: Span,
+}
+
+/// What the format string breaks down into, the same
+/// way the formatter in paris would break it down
+enum Piece {
+    Text(String),
+    Style(String, String),
+    Key(String),
+    Arg(Vec<Unit>),
+}
+
+/// Smallest thing in a format string, either a
+/// character or a whole `{}` placeholder
+enum Unit {
+    Char(char),
+    Placeholder(Placeholder),
+}
+
+/// Expects `crate_path, "format string", args...`
+pub fn pieces(input: TokenStream) -> Result<TokenStream, Error> {
+    let mut parts = split(input).into_iter();
+
+    let crate_path = parts
+        .next()
+        .ok_or_else(|| Error::new("missing crate path", Span::call_site()))?;
+    let format = string(parts.next())?;
+    let args: Vec<Arg> = parts.map(arg).collect();
+
+    let fmt_span = format.span();
+    let input = literal::unescape(&format)?;
+    let segments = format::parse(&input).map_err(|e| Error::new(e, fmt_span))?;
+    let mut units = vec![];
+
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => units.extend(text.chars().map(Unit::Char)),
+            Segment::Placeholder(p) => units.push(Unit::Placeholder(p)),
+        }
+    }
+
+    let mut unknown = vec![];
+    let pieces = tokenize(units, &mut unknown);
+
+    let mut expansion = Expansion {
+        crate_path,
+        args: &args,
+        used: vec![false; args.len()],
+        fmt_span,
+    };
+
+    let mut elements = TokenStream::new();
+
+    for piece in pieces {
+        elements.extend(expansion.piece(piece)?);
+        elements.extend(tokens(vec![Punct::new(',', Spacing::Alone).into()]));
+    }
+
+    if let Some(i) = expansion.used.iter().position(|used| !used) {
+        let message = match args[i].name {
+            Some(_) => "named argument never used",
+            None => "argument never used",
+        };
+
+        return Err(Error::new(message, args[i].span));
+    }
+
+    let mut body = TokenStream::new();
+
+    for key in unknown {
+        body.extend(warning(&key, fmt_span));
+    }
+
+    body.extend(bind(&args, group(Delimiter::Bracket, elements)));
+
+    Ok(tokens(vec![group(Delimiter::Brace, body)]))
+}
+
+/// Turns the format string into pieces, resolving every key
+/// that doesn't depend on a placeholder right away.
+fn tokenize(units: Vec<Unit>, unknown: &mut Vec<String>) -> Vec<Piece> {
+    let mut pieces = vec![];
+    let mut text = String::new();
+    let escapes: Vec<bool> = (0..units.len()).map(|i| escapes_key(&units[i..])).collect();
+    let mut units = units.into_iter().zip(escapes).peekable();
+
+    let flush = |text: &mut String, pieces: &mut Vec<Piece>| {
+        if !text.is_empty() {
+            pieces.push(Piece::Text(std::mem::take(text)));
+        }
+    };
+
+    while let Some((unit, escapes)) = units.next() {
+        let c = match unit {
+            Unit::Char(c) => c,
+            Unit::Placeholder(p) => {
+                flush(&mut text, &mut pieces);
+                pieces.push(Piece::Arg(vec![Unit::Placeholder(p)]));
+                continue;
+            }
+        };
+
+        if c != '<' {
+            text.push(c);
+            continue;
+        }
+
+        // Escaped opening, `<<red>` is written out as `<red>`
+        if escapes {
+            units.next();
+            text.push('<');
+            continue;
+        }
+
+        // Collect everything up until the end of the key, a false
+        // opening ends up being written out as text
+        let mut key = vec![Unit::Char('<')];
+        let mut closed = false;
+
+        while let Some(unit) = next_in_key(&mut units) {
+            let end = matches!(unit, Unit::Char('>'));
+            key.push(unit);
+
+            if end {
+                closed = true;
+                break;
+            }
+        }
+
+        if !closed {
+            for unit in key {
+                match unit {
+                    Unit::Char(c) => text.push(c),
+                    Unit::Placeholder(p) => {
+                        flush(&mut text, &mut pieces);
+                        pieces.push(Piece::Arg(vec![Unit::Placeholder(p)]));
+                    }
+                }
+            }
+
+            continue;
+        }
+
+        flush(&mut text, &mut pieces);
+
+        // Keys with placeholders in them can only be known at runtime
+        if key.iter().any(|u| matches!(u, Unit::Placeholder(_))) {
+            pieces.push(Piece::Arg(key));
+            continue;
+        }
+
+        let key: String = key
+            .iter()
+            .map(|u| match u {
+                Unit::Char(c) => *c,
+                Unit::Placeholder(_) => unreachable!(),
+            })
+            .collect();
+
+        match tags::resolve(&key) {
+            Tag::Ansi(ansi) => pieces.push(Piece::Style(key, ansi)),
+            Tag::Runtime => pieces.push(Piece::Key(key)),
+            Tag::Unknown => {
+                if tags::looks_like_typo(&key) {
+                    unknown.push(key.clone());
+                }
+
+                pieces.push(Piece::Key(key));
+            }
+        }
+    }
+
+    flush(&mut text, &mut pieces);
+    pieces
+}
+
+/// Next unit that's still part of the key being read,
+/// stops right before another `<`
+fn next_in_key<I>(units: &mut std::iter::Peekable<I>) -> Option<Unit>
+where
+    I: Iterator<Item = (Unit, bool)>,
+{
+    match units.peek() {
+        Some((Unit::Char('<'), _)) | None => None,
+        _ => units.next().map(|(unit, _)| unit),
+    }
+}
+
+/// Whether the units start with `<<` followed by something that looks
+/// like a key, the same check the formatter in paris does at runtime
+fn escapes_key(units: &[Unit]) -> bool {
+    let openings = units
+        .iter()
+        .take_while(|u| matches!(u, Unit::Char('<')))
+        .count();
+
+    if openings < 2 {
+        return false;
+    }
+
+    let rest = &units[openings..];
+    let blank = |unit: Option<&Unit>| matches!(unit, Some(Unit::Char(c)) if c.is_whitespace());
+
+    match rest
+        .iter()
+        .position(|u| matches!(u, Unit::Char('<') | Unit::Char('>')))
+    {
+        Some(end) if matches!(rest[end], Unit::Char('>')) => {
+            let inside = &rest[..end];
+            !inside.is_empty() && !blank(inside.first()) && !blank(inside.last())
+        }
+        _ => false,
+    }
+}
+
+struct Expansion<'a> {
+    crate_path: TokenStream,
+    args: &'a [Arg],
+    used: Vec<bool>,
+    fmt_span: Span,
+}
+
+impl<'a> Expansion<'a> {
+    /// `crate_path::formatter::Piece::Variant(...)`
+    fn piece(&mut self, piece: Piece) -> Result<TokenStream, Error> {
+        let (variant, value) = match piece {
+            Piece::Text(text) => ("Text", lit(&text)),
+            Piece::Key(key) => ("Key", lit(&key)),
+            Piece::Arg(units) => ("Arg", self.format(units)?),
+            Piece::Style(key, ansi) => {
+                let mut fields = tokens(vec![
+                    Ident::new("key", Span::call_site()).into(),
+                    Punct::new(':', Spacing::Alone).into(),
+                ]);
+                fields.extend(lit(&key));
+                fields.extend(tokens(vec![
+                    Punct::new(',', Spacing::Alone).into(),
+                    Ident::new("ansi", Span::call_site()).into(),
+                    Punct::new(':', Spacing::Alone).into(),
+                ]));
+                fields.extend(lit(&ansi));
+
+                let mut stream = self.crate_path.clone();
+                stream.extend(path(&["formatter", "Piece", "Style"]));
+                stream.extend(tokens(vec![group(Delimiter::Brace, fields)]));
+
+                return Ok(stream);
+            }
+        };
+
+        let mut stream = self.crate_path.clone();
+        stream.extend(path(&["formatter", "Piece", variant]));
+        stream.extend(tokens(vec![group(Delimiter::Parenthesis, value)]));
+
+        Ok(stream)
+    }
+
+    /// `::std::format!("...", values...)` for the given units
+    fn format(&mut self, units: Vec<Unit>) -> Result<TokenStream, Error> {
+        let mut format = String::new();
+        let mut values = vec![];
+
+        for unit in units {
+            match unit {
+                Unit::Char('{') => format.push_str("{{"),
+                Unit::Char('}') => format.push_str("}}"),
+                Unit::Char(c) => format.push(c),
+                Unit::Placeholder(p) => {
+                    format.push_str(&p.to_format(values.len()));
+
+                    values.push(self.value(&p.value)?);
+
+                    for count in p.counts.iter() {
+                        values.push(self.value(count)?);
+                    }
+                }
+            }
+        }
+
+        let mut literal = Literal::string(&format);
+        literal.set_span(self.fmt_span);
+
+        let mut args: Vec<TokenTree> = vec![literal.into()];
+
+        for value in values {
+            args.push(Punct::new(',', Spacing::Alone).into());
+            args.extend(value);
+        }
+
+        let mut stream = path(&["std", "format"]);
+        stream.extend(tokens(vec![
+            Punct::new('!', Spacing::Alone).into(),
+            group(Delimiter::Parenthesis, args.into_iter().collect()),
+        ]));
+
+        Ok(stream)
+    }
+
+    /// Whatever should be passed to `format!` for the given argument
+    fn value(&mut self, argument: &Argument) -> Result<TokenStream, Error> {
+        let positional = self.args.iter().filter(|a| a.name.is_none()).count();
+
+        let index = match argument {
+            Argument::Index(i) if *i < self.args.len() => *i,
+            Argument::Index(i) => {
+                let message = format!(
+                    "invalid reference to positional argument {} (there are {} arguments)",
+                    i, positional
+                );
+
+                return Err(Error::new(message, self.fmt_span));
+            }
+            Argument::Name(name) => {
+                let named = self
+                    .args
+                    .iter()
+                    .position(|a| a.name.as_ref().map(|n| n.to_string()).as_ref() == Some(name));
+
+                match named {
+                    Some(i) => i,
+
+                    // Not an argument, capture it from the surrounding scope
+                    None => return Ok(tokens(vec![Ident::new(name, self.fmt_span).into()])),
+                }
+            }
+        };
+
+        self.used[index] = true;
+
+        Ok(tokens(vec![
+            Punct::new('*', Spacing::Alone).into(),
+            binding(&self.args[index], index).into(),
+        ]))
+    }
+}
+
+/// Wraps the body in a match that evaluates every argument
+/// exactly once, the same way `format!` does
+fn bind(args: &[Arg], body: TokenTree) -> TokenStream {
+    if args.is_empty() {
+        return tokens(vec![body]);
+    }
+
+    let mut values: Vec<TokenTree> = vec![];
+    let mut patterns: Vec<TokenTree> = vec![];
+
+    for (i, arg) in args.iter().enumerate() {
+        values.push(Punct::new('&', Spacing::Alone).into());
+        values.push(group(Delimiter::Parenthesis, arg.expr.clone()));
+        values.push(Punct::new(',', Spacing::Alone).into());
+
+        patterns.push(binding(arg, i).into());
+        patterns.push(Punct::new(',', Spacing::Alone).into());
+    }
+
+    let arm = tokens(vec![
+        group(Delimiter::Parenthesis, values.into_iter().collect()),
+        group(
+            Delimiter::Brace,
+            tokens(vec![
+                group(Delimiter::Parenthesis, patterns.into_iter().collect()),
+                Punct::new('=', Spacing::Joint).into(),
+                Punct::new('>', Spacing::Alone).into(),
+                body,
+            ]),
+        ),
+    ]);
+
+    let mut stream = tokens(vec![Ident::new("match", Span::call_site()).into()]);
+    stream.extend(arm);
+    stream
+}
+
+/// Name the argument is bound to inside the expansion
+fn binding(arg: &Arg, index: usize) -> Ident {
+    match &arg.name {
+        Some(name) => name.clone(),
+        None => Ident::new(&format!("__paris_arg_{}", index), Span::call_site()),
+    }
+}
+
+/// There's no stable way of emitting warnings from a proc
+/// macro so this uses a deprecated item to get one out.
+fn warning(key: &str, span: Span) -> TokenStream {
+    let note = format!(
+        "`{}` isn't a known tag, it will be written out as is unless it's an icon added at runtime",
+        key
+    );
+
+    let attribute = |name: &str, args: TokenStream| -> Vec<TokenTree> {
+        vec![
+            Punct::new('#', Spacing::Alone).into(),
+            group(
+                Delimiter::Bracket,
+                tokens(vec![
+                    Ident::new(name, span).into(),
+                    group(Delimiter::Parenthesis, args),
+                ]),
+            ),
+        ]
+    };
+
+    let mut note_args = tokens(vec![
+        Ident::new("note", span).into(),
+        Punct::new('=', Spacing::Alone).into(),
+    ]);
+    note_args.extend(tokens(vec![lit_tree(&note, span)]));
+
+    let mut item = vec![];
+    item.extend(attribute("deprecated", note_args));
+    item.extend(attribute(
+        "allow",
+        tokens(vec![Ident::new("non_camel_case_types", span).into()]),
+    ));
+    item.extend(vec![
+        Ident::new("struct", span).into(),
+        Ident::new("unknown_paris_tag", span).into(),
+        Punct::new(';', Spacing::Alone).into(),
+        Ident::new("let", span).into(),
+        Ident::new("_", span).into(),
+        Punct::new('=', Spacing::Alone).into(),
+        Ident::new("unknown_paris_tag", span).into(),
+        Punct::new(';', Spacing::Alone).into(),
+    ]);
+
+    tokens(vec![group(Delimiter::Brace, item.into_iter().collect())])
+}
+
+/// Splits the input on every top level comma
+fn split(input: TokenStream) -> Vec<TokenStream> {
+    let mut parts = vec![];
+    let mut current = vec![];
+
+    for token in input {
+        match &token {
+            TokenTree::Punct(p) if p.as_char() == ',' => {
+                parts.push(current.drain(..).collect());
+            }
+            _ => current.push(token),
+        }
+    }
+
+    if !current.is_empty() {
+        parts.push(current.into_iter().collect());
+    }
+
+    parts
+}
+
+/// Removes the invisible groups `macro_rules!` wraps
+/// around fragments like `$e:expr` or `$l:literal`
+fn unwrap(stream: TokenStream) -> TokenStream {
+    let mut tokens = stream.clone().into_iter();
+
+    match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Group(g)), None) if g.delimiter() == Delimiter::None => unwrap(g.stream()),
+        _ => stream,
+    }
+}
+
+fn string(part: Option<TokenStream>) -> Result<Literal, Error> {
+    let part = part.map(unwrap).unwrap_or_default();
+    let mut tokens = part.into_iter();
+
+    match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(l)), None) => Ok(l),
+        (Some(t), _) => Err(Error::new(
+            "format argument must be a string literal",
+            t.span(),
+        )),
+        (None, _) => Err(Error::new(
+            "requires at least a format string argument",
+            Span::call_site(),
+        )),
+    }
+}
+
+fn arg(part: TokenStream) -> Arg {
+    let part = unwrap(part);
+    let tokens: Vec<TokenTree> = part.clone().into_iter().collect();
+    let span = tokens
+        .first()
+        .map(|t| t.span())
+        .unwrap_or_else(Span::call_site);
+
+    if let (Some(TokenTree::Ident(name)), Some(TokenTree::Punct(eq))) =
+        (tokens.first(), tokens.get(1))
+    {
+        if eq.as_char() == '=' && eq.spacing() == Spacing::Alone {
+            return Arg {
+                name: Some(name.clone()),
+                expr: tokens[2..].iter().cloned().collect(),
+                span,
+            };
+        }
+    }
+
+    Arg {
+        name: None,
+        expr: part,
+        span,
+    }
+}
+
+fn tokens(trees: Vec<TokenTree>) -> TokenStream {
+    trees.into_iter().collect()
+}
+
+fn group(delimiter: Delimiter, stream: TokenStream) -> TokenTree {
+    Group::new(delimiter, stream).into()
+}
+
+fn lit(value: &str) -> TokenStream {
+    tokens(vec![lit_tree(value, Span::call_site())])
+}
+
+fn lit_tree(value: &str, span: Span) -> TokenTree {
+    let mut literal = Literal::string(value);
+    literal.set_span(span);
+    literal.into()
+}
+
+/// `::a::b::c`
+fn path(segments: &[&str]) -> TokenStream {
+    let mut stream = vec![];
+
+    for segment in segments {
+        stream.push(Punct::new(':', Spacing::Joint).into());
+        stream.push(Punct::new(':', Spacing::Alone).into());
+        stream.push(Ident::new(segment, Span::call_site()).into());
+    }
+
+    tokens(stream)
+}
@@ -0,0 +1,317 @@
+//! Splits a `format!` string into plain text and `{}` placeholders
+//! so each placeholder can be formatted on its own.
+
+/// Which argument a placeholder (or one of its counts) refers to
+#[derive(Clone)]
+pub enum Argument {
+    Index(usize),
+    Name(String),
+}
+
+/// Either plain spec text or a pointer into
+/// the counts of the placeholder it belongs to
+pub enum SpecPart {
+    Text(String),
+    Count(usize),
+}
+
+/// A single `{...}` with its value, every count (`width$`, `.*`)
+/// it needs and its spec, which points into those counts.
+pub struct Placeholder {
+    pub value: Argument,
+    pub counts: Vec<Argument>,
+    pub spec: Vec<SpecPart>,
+}
+
+impl Placeholder {
+    /// Writes the placeholder back out, with the value at
+    /// position `at` and its counts right after it
+    pub fn to_format(&self, at: usize) -> String {
+        let mut output = format!("{{{}", at);
+
+        if !self.spec.is_empty() {
+            output.push(':');
+        }
+
+        for part in self.spec.iter() {
+            match part {
+                SpecPart::Text(text) => output.push_str(text),
+                SpecPart::Count(i) => output.push_str(&format!("{}$", at + 1 + i)),
+            }
+        }
+
+        output.push('}');
+        output
+    }
+}
+
+pub enum Segment {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+/// Parses the given format string, returns an error
+/// message if it isn't a valid one
+pub fn parse(input: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = vec![];
+    let mut text = String::new();
+    let mut next = 0;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut inner = String::new();
+                let mut closed = false;
+
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+
+                    inner.push(c);
+                }
+
+                if !closed {
+                    return Err(
+                        "invalid format string: expected `}` but string was terminated".into(),
+                    );
+                }
+
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+
+                segments.push(Segment::Placeholder(placeholder(&inner, &mut next)?));
+            }
+            '}' => return Err("invalid format string: unmatched `}` found".into()),
+            _ => text.push(c),
+        }
+    }
+
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+
+    Ok(segments)
+}
+
+/// Parses whatever is between `{` and `}`
+fn placeholder(inner: &str, next: &mut usize) -> Result<Placeholder, String> {
+    let (argument, spec) = match inner.find(':') {
+        Some(i) => (inner[..i].trim(), &inner[i + 1..]),
+        None => (inner.trim(), ""),
+    };
+
+    // The precision of `.*` comes before the value itself
+    let mut counts = vec![];
+    let spec = rewrite_spec(spec, &mut counts, next)?;
+
+    let value = if argument.is_empty() {
+        *next += 1;
+        Argument::Index(*next - 1)
+    } else {
+        reference(argument)?
+    };
+
+    Ok(Placeholder {
+        value,
+        counts,
+        spec,
+    })
+}
+
+/// Turns a written reference into an argument
+fn reference(argument: &str) -> Result<Argument, String> {
+    if argument.chars().all(|c| c.is_ascii_digit()) {
+        return argument
+            .parse()
+            .map(Argument::Index)
+            .map_err(|_| format!("invalid argument `{}`", argument));
+    }
+
+    let valid = argument
+        .chars()
+        .enumerate()
+        .all(|(i, c)| c == '_' || c.is_alphabetic() || (i > 0 && c.is_alphanumeric()));
+
+    if !valid {
+        return Err(format!(
+            "invalid format string: invalid argument name `{}`",
+            argument
+        ));
+    }
+
+    Ok(Argument::Name(argument.to_string()))
+}
+
+/// Rewrites every count (`width$`, `.*`) inside a spec so they point
+/// at positions inside `counts`, which come right after the value.
+fn rewrite_spec(
+    spec: &str,
+    counts: &mut Vec<Argument>,
+    next: &mut usize,
+) -> Result<Vec<SpecPart>, String> {
+    let chars: Vec<char> = spec.chars().collect();
+    let mut output = Spec(vec![]);
+    let mut i = 0;
+
+    let is_align = |c: char| c == '<' || c == '^' || c == '>';
+
+    // Fill and alignment
+    if chars.len() >= 2 && is_align(chars[1]) {
+        output.push_chars(&chars[..2]);
+        i = 2;
+    } else if !chars.is_empty() && is_align(chars[0]) {
+        output.push_chars(&chars[..1]);
+        i = 1;
+    }
+
+    // Sign and alternate flags
+    while i < chars.len() && (chars[i] == '+' || chars[i] == '-' || chars[i] == '#') {
+        output.push_chars(&chars[i..=i]);
+        i += 1;
+    }
+
+    // Zero padding, as long as it isn't `0$`
+    if i < chars.len() && chars[i] == '0' && chars.get(i + 1) != Some(&'$') {
+        output.push_chars(&['0']);
+        i += 1;
+    }
+
+    i = count(&chars, i, &mut output, counts)?;
+
+    if i < chars.len() && chars[i] == '.' {
+        output.push_chars(&['.']);
+        i += 1;
+
+        if chars.get(i) == Some(&'*') {
+            counts.push(Argument::Index(*next));
+            *next += 1;
+
+            output.0.push(SpecPart::Count(counts.len() - 1));
+            i += 1;
+        } else {
+            i = count(&chars, i, &mut output, counts)?;
+        }
+    }
+
+    // Whatever is left is the type
+    output.push_chars(&chars[i..]);
+
+    Ok(output.0)
+}
+
+/// Collects spec parts, merging text together
+struct Spec(Vec<SpecPart>);
+
+impl Spec {
+    fn push_chars(&mut self, chars: &[char]) {
+        if chars.is_empty() {
+            return;
+        }
+
+        if let Some(SpecPart::Text(text)) = self.0.last_mut() {
+            text.extend(chars);
+            return;
+        }
+
+        self.0.push(SpecPart::Text(chars.iter().collect()));
+    }
+}
+
+/// Reads a width or precision starting at `i`, returns where it stopped
+fn count(
+    chars: &[char],
+    start: usize,
+    output: &mut Spec,
+    counts: &mut Vec<Argument>,
+) -> Result<usize, String> {
+    let mut end = start;
+
+    while end < chars.len() && (chars[end] == '_' || chars[end].is_alphanumeric()) {
+        end += 1;
+    }
+
+    let word: String = chars[start..end].iter().collect();
+
+    if chars.get(end) == Some(&'$') {
+        counts.push(reference(&word)?);
+        output.0.push(SpecPart::Count(counts.len() - 1));
+
+        return Ok(end + 1);
+    }
+
+    if !word.is_empty() && word.chars().all(|c| c.is_ascii_digit()) {
+        output.push_chars(&chars[start..end]);
+        return Ok(end);
+    }
+
+    // Not a count, probably the type
+    Ok(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formats(input: &str) -> Vec<String> {
+        parse(input)
+            .unwrap()
+            .iter()
+            .map(|s| match s {
+                Segment::Text(t) => t.clone(),
+                Segment::Placeholder(p) => p.to_format(0),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn text_and_placeholders() {
+        assert_eq!(
+            formats("a {} {{b}} {:>5}"),
+            vec!["a ", "{0}", " {b} ", "{0:>5}"]
+        );
+    }
+
+    #[test]
+    fn counts_point_after_the_value() {
+        assert_eq!(
+            formats("{:>1$} {:.*} {x:w$.p$}"),
+            vec!["{0:>1$}", " ", "{0:.1$}", " ", "{0:1$.2$}"]
+        );
+    }
+
+    #[test]
+    fn implicit_positions() {
+        let segments = parse("{} {:.*} {0} {}").unwrap();
+        let values: Vec<String> = segments
+            .iter()
+            .filter_map(|s| match s {
+                Segment::Placeholder(p) => Some(match &p.value {
+                    Argument::Index(i) => i.to_string(),
+                    Argument::Name(n) => n.clone(),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(values, vec!["0", "2", "0", "3"]);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse("{").is_err());
+        assert!(parse("}").is_err());
+        assert!(parse("{a-b}").is_err());
+    }
+}
@@ -0,0 +1,28 @@
+//! Compile time companion to [paris](https://docs.rs/paris).
+//!
+//! Parses the format string given to the paris logging macros
+//! while compiling, so that every color and style tag in it is
+//! already an escape sequence by the time the program runs. Only
+//! the arguments, and tags that can't be known ahead of time like icons,
+//! are left for paris to deal with at runtime.
+//!
+//! There's nothing in here meant to be used directly,
+//! enable the `macros` feature of paris instead.
+extern crate proc_macro;
+
+mod error;
+mod expand;
+mod format;
+mod literal;
+mod tables;
+mod tags;
+
+use proc_macro::TokenStream;
+
+/// Breaks a format string down into the pieces paris
+/// writes out. Expects `crate_path, "format string", args...`
+#[doc(hidden)]
+#[proc_macro]
+pub fn pieces(input: TokenStream) -> TokenStream {
+    expand::pieces(input).unwrap_or_else(|e| e.into_compile_error())
+}
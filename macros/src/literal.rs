@@ -0,0 +1,115 @@
+use crate::error::Error;
+use proc_macro::Literal;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Returns the actual contents of a string literal,
+/// with all escapes resolved. Raw strings are supported.
+pub fn unescape(literal: &Literal) -> Result<String, Error> {
+    let source = literal.to_string();
+    let span = literal.span();
+
+    if let Some(raw) = source.strip_prefix('r') {
+        let hashes = raw.chars().take_while(|c| *c == '#').count();
+        let start = 1 + hashes + 1;
+        let end = source.len() - hashes - 1;
+
+        if source.len() < start || end < start {
+            return Err(Error::new("expected a string literal", span));
+        }
+
+        return Ok(source[start..end].to_string());
+    }
+
+    if !source.starts_with('"') || !source.ends_with('"') || source.len() < 2 {
+        return Err(Error::new("format argument must be a string literal", span));
+    }
+
+    unescape_str(&source[1..source.len() - 1]).map_err(|message| Error::new(message, span))
+}
+
+/// Resolves every escape in the contents of a normal string literal
+fn unescape_str(inner: &str) -> Result<String, &'static str> {
+    let mut output = String::with_capacity(inner.len());
+    let mut chars = inner.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+
+        let escaped = match chars.next() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some('\\') => '\\',
+            Some('\'') => '\'',
+            Some('"') => '"',
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                let code = u8::from_str_radix(&hex, 16)
+                    .map_err(|_| "invalid \\x escape in string literal")?;
+
+                code as char
+            }
+            Some('u') => {
+                let code: String = chars
+                    .by_ref()
+                    .skip_while(|c| *c == '{')
+                    .take_while(|c| *c != '}')
+                    .filter(|c| *c != '_')
+                    .collect();
+
+                u32::from_str_radix(&code, 16)
+                    .ok()
+                    .and_then(std::char::from_u32)
+                    .ok_or("invalid \\u escape in string literal")?
+            }
+            Some('\r') if chars.peek() == Some(&'\n') => {
+                chars.next();
+                skip_whitespace(&mut chars);
+                continue;
+            }
+            Some('\n') => {
+                skip_whitespace(&mut chars);
+                continue;
+            }
+            _ => return Err("unknown escape in string literal"),
+        };
+
+        output.push(escaped);
+    }
+
+    Ok(output)
+}
+
+/// Line continuation, skips the same whitespace rustc does
+/// at the start of the next line, `\r\n` line endings included
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars
+        .peek()
+        .is_some_and(|c| matches!(c, ' ' | '\t' | '\n' | '\r'))
+    {
+        chars.next();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes() {
+        assert_eq!(unescape_str(r"a\tb\x41\u{1F680}\\").unwrap(), "a\tbA🚀\\");
+        assert!(unescape_str(r"\q").is_err());
+    }
+
+    #[test]
+    fn line_continuations() {
+        assert_eq!(unescape_str("a\\\n    b").unwrap(), "ab");
+        assert_eq!(unescape_str("a\\\r\n    b").unwrap(), "ab");
+        assert_eq!(unescape_str("a\\\r\n\r\n\tb").unwrap(), "ab");
+    }
+}
@@ -0,0 +1 @@
+../../src/formatter/tables.rs
\ No newline at end of file
@@ -0,0 +1,168 @@
+//! Compile time equivalent of the key parsing in paris. Only
+//! resolves keys that turn into escape sequences, anything else
+//! (icons for example) is left for paris to handle at runtime.
+//!
+//! The keys themselves come from the same table file paris
+//! uses, there's a test over there making sure both agree.
+use crate::tables::{clean, color, compound, style, COLORS, FIGURES, LOG_ICONS, STYLES};
+
+/// What a key turned out to be
+pub enum Tag {
+    /// Key is a color or style, already
+    /// converted to its escape sequence
+    Ansi(String),
+
+    /// Key is valid but can only be resolved at runtime
+    Runtime,
+
+    /// Nothing that paris knows about
+    Unknown,
+}
+
+/// Figures out what the given key, including
+/// the surrounding `<` and `>`, stands for
+pub fn resolve(key: &str) -> Tag {
+    let clean = clean(key);
+
+    // Emoji can be turned off at runtime, so
+    // shortcodes are always left for later
+    if is_icon(&clean) || is_emoji(key) {
+        return Tag::Runtime;
+    }
+
+    // Closing and reset keys depend on what's open at runtime
+    if let Some(name) = clean.strip_prefix('/') {
+        let known = name.is_empty()
+            || name == "/"
+            || name == "//"
+            || style(&clean).is_some()
+            || !matches!(resolve(&format!("<{}>", name)), Tag::Unknown);
+
+        return if known { Tag::Runtime } else { Tag::Unknown };
+    }
+
+    if let Some(code) = style(&clean).or_else(|| color(&clean)) {
+        return Tag::Ansi(format!("\x1B[{}m", code));
+    }
+
+    if let Some(codes) = compound(&clean) {
+        return Tag::Ansi(format!("\x1B[{}m", codes));
+    }
+
+    Tag::Unknown
+}
+
+/// Whether a key that isn't known is close enough to one that
+/// is to probably be a typo, like `<bule>` or `<bold rde>`. Generics
+/// like `<T>` or `<u8>` in a message were never meant to be tags.
+pub fn looks_like_typo(key: &str) -> bool {
+    let name = clean(key).to_lowercase();
+    let name = name.trim_start_matches('/');
+
+    if name.chars().count() < 3
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '+')
+    {
+        return false;
+    }
+
+    let words: Vec<&str> = name
+        .split([' ', '+'])
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    if words.len() > 1
+        && words
+            .iter()
+            .any(|word| word.len() > 2 && (style(word).is_some() || color(word).is_some()))
+    {
+        return true;
+    }
+
+    let limit = if name.chars().count() > 4 { 2 } else { 1 };
+    let colors = COLORS
+        .iter()
+        .flat_map(|(color, _)| vec![color.to_string(), format!("on {}", color)]);
+
+    STYLES
+        .iter()
+        .map(|(style, _)| style.to_string())
+        .chain(colors)
+        .chain(LOG_ICONS.iter().map(|icon| icon.to_string()))
+        .chain(FIGURES.iter().map(|(figure, _, _)| figure.to_string()))
+        .any(|known| distance(name, &known) <= limit)
+}
+
+/// Edits needed to turn one into the other, swapping two
+/// neighbouring characters counts as a single edit
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+fn is_icon(key: &str) -> bool {
+    let key = key.to_lowercase();
+
+    LOG_ICONS.contains(&key.as_ref()) || FIGURES.iter().any(|(name, _, _)| *name == key)
+}
+
+/// Looks like `<:rocket:>` or `<emoji-rocket>`
+fn is_emoji(key: &str) -> bool {
+    let key = key.trim_matches(|c| c == '<' || c == '>');
+
+    (key.len() > 2 && key.starts_with(':') && key.ends_with(':'))
+        || ["emoji-", "emoji_", "emoji "]
+            .iter()
+            .any(|prefix| key.len() > prefix.len() && key.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typos() {
+        assert!(looks_like_typo("<bule>"));
+        assert!(looks_like_typo("<magneta>"));
+        assert!(looks_like_typo("</gren>"));
+        assert!(looks_like_typo("<bold rde>"));
+        assert!(looks_like_typo("<arow-right>"));
+    }
+
+    #[test]
+    fn not_typos() {
+        assert!(!looks_like_typo("<T>"));
+        assert!(!looks_like_typo("<u8>"));
+        assert!(!looks_like_typo("<Vec>"));
+        assert!(!looks_like_typo("<html>"));
+        assert!(!looks_like_typo("<String>"));
+        assert!(!looks_like_typo("<&str>"));
+        assert!(!looks_like_typo("<a b>"));
+    }
+}
@@ -1,3 +1,10 @@
+use std::io::{self, Write};
+
+/// Attributes [`leaves_style`](struct.Ansi.html) keeps track of:
+/// intensity, italic, underline, blink, reverse, hidden,
+/// strikethrough, foreground, background and anything else
+const ATTRIBUTES: usize = 10;
+
 /// Escape whatever's being sent
 /// in here to an ansi code
 pub struct Ansi {}
@@ -9,8 +16,121 @@ impl Ansi {
         format!("\x1B[{}m", code)
     }
 
+    /// Whether the given text ends with some color or style still
+    /// active, going by the escape sequences in it. `None` if there
+    /// aren't any escape sequences to go by.
+    pub(crate) fn leaves_style(text: &str) -> Option<bool> {
+        let mut active = None;
+        let mut on = [false; ATTRIBUTES];
+        let mut rest = text;
+
+        while let Some(start) = rest.find("\x1B[") {
+            rest = &rest[start + 2..];
+
+            let end = match rest.find(|c: char| ('@'..='~').contains(&c)) {
+                Some(end) => end,
+                None => break,
+            };
+
+            // Only 'm' sequences change the style, anything else moves the cursor or similar
+            if rest[end..].starts_with('m') {
+                let mut params = rest[..end].split(';');
+
+                while let Some(param) = params.next() {
+                    if param.is_empty() || param == "0" {
+                        on = [false; ATTRIBUTES];
+                    } else {
+                        let (attribute, set) = Self::attribute(param);
+                        on[attribute] = set;
+                    }
+
+                    // The color parts of `38;5;n` and `38;2;r;g;b` aren't resets, even when 0
+                    if param == "38" || param == "48" {
+                        match params.next() {
+                            Some("5") => {
+                                params.next();
+                            }
+                            Some("2") => {
+                                params.nth(2);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+
+                active = Some(on.contains(&true));
+            }
+
+            rest = &rest[end..];
+        }
+
+        active
+    }
+
+    /// Which attribute a parameter turns on or off, bold and dimmed
+    /// share one since `22` turns off both. Anything unknown can
+    /// only be turned off by resetting everything.
+    fn attribute(param: &str) -> (usize, bool) {
+        match param.parse::<u8>() {
+            Ok(1) | Ok(2) => (0, true),
+            Ok(22) => (0, false),
+            Ok(3) => (1, true),
+            Ok(23) => (1, false),
+            Ok(4) => (2, true),
+            Ok(24) => (2, false),
+            Ok(5) | Ok(6) => (3, true),
+            Ok(25) => (3, false),
+            Ok(7) => (4, true),
+            Ok(27) => (4, false),
+            Ok(8) => (5, true),
+            Ok(28) => (5, false),
+            Ok(9) => (6, true),
+            Ok(29) => (6, false),
+            Ok(30..=38) | Ok(90..=97) => (7, true),
+            Ok(39) => (7, false),
+            Ok(40..=48) | Ok(100..=107) => (8, true),
+            Ok(49) => (8, false),
+            _ => (ATTRIBUTES - 1, true),
+        }
+    }
+
     /// Clears the line of all characters
     pub fn clear_line() {
-        print!("\r\x1B[2K");
+        crate::output::ignore_broken_pipe(Self::try_clear_line(), "stdout");
+    }
+
+    /// Clears the line of all characters, returns
+    /// any error that happened while writing
+    pub fn try_clear_line() -> io::Result<()> {
+        io::stdout().lock().write_all(b"\r\x1B[2K")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn style_left_active() {
+        assert_eq!(Ansi::leaves_style("nothing here"), None);
+        assert_eq!(Ansi::leaves_style("\x1B[31mred"), Some(true));
+        assert_eq!(Ansi::leaves_style("\x1B[1;31mred\x1B[0m"), Some(false));
+        assert_eq!(Ansi::leaves_style("\x1B[31mred\x1B[m"), Some(false));
+        assert_eq!(Ansi::leaves_style("\x1B[31mred\x1B[2K"), Some(true));
+        assert_eq!(Ansi::leaves_style("\x1B[2K"), None);
+        assert_eq!(Ansi::leaves_style("\x1B[1;0m"), Some(false));
+        assert_eq!(Ansi::leaves_style("\x1B[0;31m"), Some(true));
+        assert_eq!(Ansi::leaves_style("\x1B[38;5;0m"), Some(true));
+        assert_eq!(Ansi::leaves_style("\x1B[48;2;0;0;0m"), Some(true));
+    }
+
+    #[test]
+    fn partial_resets() {
+        assert_eq!(Ansi::leaves_style("\x1B[31mred\x1B[39m"), Some(false));
+        assert_eq!(Ansi::leaves_style("\x1B[1;44mx\x1B[22;49m"), Some(false));
+        assert_eq!(Ansi::leaves_style("\x1B[1;31mx\x1B[22m"), Some(true));
+        assert_eq!(Ansi::leaves_style("\x1B[2mx\x1B[22m"), Some(false));
+        assert_eq!(Ansi::leaves_style("\x1B[23m"), Some(false));
+        assert_eq!(Ansi::leaves_style("\x1B[53mx\x1B[39m"), Some(true));
     }
 }
@@ -1,15 +1,19 @@
 use crate::formatter::keys::Key;
+use std::borrow::Cow;
 
 pub struct CustomStyle<'a> {
     key: String,
-    colors: Vec<Key<'a>>,
+    colors: Vec<Cow<'a, str>>,
 }
 
 impl<'a> CustomStyle<'a> {
-    pub fn new(key: &str, colors: Vec<&'a str>) -> Self {
+    pub fn new<C>(key: &str, colors: Vec<C>) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+    {
         Self {
             key: format!("<{}>", key),
-            colors: colors.iter().map(|s| Key::new(s)).collect(),
+            colors: colors.into_iter().map(Into::into).collect(),
         }
     }
 
@@ -17,15 +21,22 @@ impl<'a> CustomStyle<'a> {
         &self.key
     }
 
-    pub fn expand(&self) -> String {
-        let mut colors: Vec<String> = Vec::with_capacity(2);
-
-        // Turn it into the ansi values it should be
-        for color in self.colors.iter() {
-            colors.push(color.to_ansi());
-        }
+    /// The key without its brackets
+    pub fn name(&self) -> &str {
+        &self.key[1..self.key.len() - 1]
+    }
 
-        colors.join("")
+    /// Turns every color into whatever `expand` says it
+    /// is and puts them all together. Colors can be
+    /// other custom styles so it's up to the caller.
+    pub fn expand<F>(&self, mut expand: F) -> String
+    where
+        F: FnMut(&Key) -> String,
+    {
+        self.colors
+            .iter()
+            .map(|color| expand(&Key::new(color)))
+            .collect()
     }
 }
 
@@ -39,16 +50,31 @@ mod tests {
         let style = CustomStyle::new("lol", vec!["blue"]);
         let color = Key::new("blue");
 
-        assert_eq!(style.expand(), color.to_ansi());
+        assert_eq!(style.expand(|k| k.to_ansi()), color.to_ansi());
     }
 
     #[test]
     fn ansi_expansion_multiple() {
         let style = CustomStyle::new("lol", vec!["blue", "bold", "on-green"]);
-        let colors = vec![Key::new("blue"), Key::new("bold"), Key::new("on-green")];
+        let colors = [Key::new("blue"), Key::new("bold"), Key::new("on-green")];
 
         let generated: String = colors.iter().map(|k| k.to_ansi()).collect();
 
-        assert_eq!(style.expand(), generated);
+        assert_eq!(style.expand(|k| k.to_ansi()), generated);
+    }
+
+    #[test]
+    fn owned_colors() {
+        let style = CustomStyle::new("lol", vec![String::from("blue")]);
+
+        assert_eq!(style.expand(|k| k.to_ansi()), Key::new("blue").to_ansi());
+    }
+
+    #[test]
+    fn name() {
+        let style = CustomStyle::new("error-base", vec!["red"]);
+
+        assert_eq!(style.key(), "<error-base>");
+        assert_eq!(style.name(), "error-base");
     }
 }
@@ -0,0 +1,214 @@
+//! Emoji shortcodes, usable as `<:rocket:>` or `<emoji-rocket>`
+use super::keys::FromKey;
+
+/// Shortcode, the emoji, and what to write instead when
+/// emoji are turned off. Without one, the shortcode is written.
+const EMOJI: &[(&str, &str, Option<&str>)] = &[
+    ("+1", "👍", Some("+1")),
+    ("-1", "👎", Some("-1")),
+    ("100", "💯", Some("100")),
+    ("alarm_clock", "⏰", None),
+    ("alien", "👽", None),
+    ("ambulance", "🚑", None),
+    ("apple", "🍎", None),
+    ("arrow_down", "⬇️", Some("v")),
+    ("arrow_up", "⬆️", Some("^")),
+    ("art", "🎨", None),
+    ("beer", "🍺", None),
+    ("bell", "🔔", None),
+    ("bomb", "💣", None),
+    ("bookmark", "🔖", None),
+    ("boom", "💥", None),
+    ("broken_heart", "💔", Some("</3")),
+    ("bug", "🐛", None),
+    ("bulb", "💡", None),
+    ("cake", "🍰", None),
+    ("calendar", "📅", None),
+    ("checkered_flag", "🏁", None),
+    ("clap", "👏", None),
+    ("clipboard", "📋", None),
+    ("cloud", "☁️", None),
+    ("coffee", "☕", None),
+    ("computer", "💻", None),
+    ("construction", "🚧", None),
+    ("construction_worker", "👷", None),
+    ("cry", "😢", Some(":'(")),
+    ("earth_americas", "🌎", None),
+    ("email", "📧", None),
+    ("evergreen_tree", "🌲", None),
+    ("exclamation", "❗", Some("!")),
+    ("eyes", "👀", None),
+    ("fire", "🔥", None),
+    ("floppy_disk", "💾", None),
+    ("gear", "⚙️", None),
+    ("ghost", "👻", None),
+    ("gift", "🎁", None),
+    ("globe_with_meridians", "🌐", None),
+    ("green_heart", "💚", Some("<3")),
+    ("grin", "😁", Some(":D")),
+    ("hammer", "🔨", None),
+    ("heart", "❤️", Some("<3")),
+    ("heavy_check_mark", "✔️", Some("v")),
+    ("heavy_minus_sign", "➖", Some("-")),
+    ("heavy_multiplication_x", "✖️", Some("x")),
+    ("heavy_plus_sign", "➕", Some("+")),
+    ("hourglass", "⌛", None),
+    ("information_source", "ℹ️", Some("i")),
+    ("iphone", "📱", None),
+    ("joy", "😂", Some(":'D")),
+    ("key", "🔑", None),
+    ("link", "🔗", None),
+    ("lipstick", "💄", None),
+    ("lock", "🔒", None),
+    ("mag", "🔍", None),
+    ("medal", "🏅", None),
+    ("memo", "📝", None),
+    ("moneybag", "💰", None),
+    ("muscle", "💪", None),
+    ("new", "🆕", Some("NEW")),
+    ("no_bell", "🔕", None),
+    ("no_entry", "⛔", None),
+    ("ok", "🆗", Some("OK")),
+    ("package", "📦", None),
+    ("pencil2", "✏️", None),
+    ("pizza", "🍕", None),
+    ("poop", "💩", None),
+    ("pray", "🙏", None),
+    ("pushpin", "📌", None),
+    ("question", "❓", Some("?")),
+    ("rainbow", "🌈", None),
+    ("recycle", "♻️", None),
+    ("red_circle", "🔴", None),
+    ("robot", "🤖", None),
+    ("rocket", "🚀", None),
+    ("rotating_light", "🚨", None),
+    ("scream", "😱", Some(":O")),
+    ("seedling", "🌱", None),
+    ("shield", "🛡️", None),
+    ("skull", "💀", None),
+    ("smile", "😄", Some(":D")),
+    ("snowflake", "❄️", None),
+    ("sob", "😭", Some(":'(")),
+    ("sos", "🆘", Some("SOS")),
+    ("sparkles", "✨", None),
+    ("speech_balloon", "💬", None),
+    ("star", "⭐", Some("*")),
+    ("star2", "🌟", Some("*")),
+    ("stopwatch", "⏱️", None),
+    ("sunglasses", "😎", Some("B)")),
+    ("sunny", "☀️", None),
+    ("sweat_smile", "😅", Some("^^'")),
+    ("tada", "🎉", None),
+    ("thinking", "🤔", Some(":-/")),
+    ("thumbsdown", "👎", Some("-1")),
+    ("thumbsup", "👍", Some("+1")),
+    ("triangular_flag_on_post", "🚩", None),
+    ("trophy", "🏆", None),
+    ("truck", "🚚", None),
+    ("umbrella", "☔", None),
+    ("unlock", "🔓", None),
+    ("warning", "⚠️", Some("!")),
+    ("wave", "👋", None),
+    ("white_check_mark", "✅", Some("[x]")),
+    ("wink", "😉", Some(";)")),
+    ("wrench", "🔧", None),
+    ("x", "❌", Some("x")),
+    ("zap", "⚡", None),
+];
+
+/// Emoji written as shortcodes in keys
+pub struct Emoji;
+
+impl Emoji {
+    /// What to write for the key when emoji are turned off
+    #[cfg(not(feature = "no_logger"))]
+    pub fn fallback(key: &str) -> Option<String> {
+        let (code, _, fallback) = Self::find(key)?;
+
+        match fallback {
+            Some(fallback) => Some(fallback.to_string()),
+            None => Some(format!(":{}:", code)),
+        }
+    }
+
+    /// Looks up the emoji for a key like `:rocket:` or `emoji-rocket`
+    fn find(key: &str) -> Option<&'static (&'static str, &'static str, Option<&'static str>)> {
+        let name = shortcode(key)?;
+
+        EMOJI.iter().find(|(code, _, _)| *code == name)
+    }
+}
+
+impl FromKey for Emoji {
+    fn from_key(key: &str) -> Option<String> {
+        Self::find(key).map(|(_, emoji, _)| emoji.to_string())
+    }
+}
+
+/// The shortcode inside the key, with spaces and dashes turned into
+/// underscores. The leading dash in `:-1:` is part of the name.
+fn shortcode(key: &str) -> Option<String> {
+    let key = key.trim_matches(|c| c == '<' || c == '>');
+
+    let name = match key.strip_prefix(':').and_then(|k| k.strip_suffix(':')) {
+        Some(name) => name,
+        None => key
+            .strip_prefix("emoji")
+            .filter(|rest| rest.starts_with(['-', '_', ' ']))
+            .map(|rest| &rest[1..])?,
+    };
+
+    if name.is_empty() {
+        return None;
+    }
+
+    let code: String = name
+        .char_indices()
+        .map(|(idx, c)| match c {
+            ' ' | '-' if idx > 0 => '_',
+            c => c,
+        })
+        .collect();
+
+    Some(code.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortcodes() {
+        assert_eq!(Emoji::from_key("<:rocket:>").unwrap(), "🚀");
+        assert_eq!(Emoji::from_key("<emoji-rocket>").unwrap(), "🚀");
+        assert_eq!(Emoji::from_key("<emoji_white-check mark>").unwrap(), "✅");
+        assert_eq!(Emoji::from_key("<:-1:>").unwrap(), "👎");
+        assert_eq!(Emoji::from_key("<:Tada:>").unwrap(), "🎉");
+    }
+
+    #[test]
+    fn not_emoji() {
+        assert!(Emoji::from_key("<rocket>").is_none());
+        assert!(Emoji::from_key("<:nope:>").is_none());
+        assert!(Emoji::from_key("<emojirocket>").is_none());
+        assert!(Emoji::from_key("<::>").is_none());
+        assert!(Emoji::from_key("<:>").is_none());
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_logger"))]
+    fn fallbacks() {
+        assert_eq!(Emoji::fallback("<:heart:>").unwrap(), "<3");
+        assert_eq!(Emoji::fallback("<emoji-rocket>").unwrap(), ":rocket:");
+        assert!(Emoji::fallback("<red>").is_none());
+    }
+
+    #[test]
+    fn sorted() {
+        let codes: Vec<&str> = EMOJI.iter().map(|(code, _, _)| *code).collect();
+        let mut sorted = codes.clone();
+        sorted.sort_unstable();
+
+        assert_eq!(codes, sorted);
+    }
+}
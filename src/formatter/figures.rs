@@ -0,0 +1,38 @@
+//! The rest of the [figures](https://github.com/sindresorhus/figures)
+//! catalogue, usable as keys like `<arrow-right>` or `<star>`.
+//! The five main ones live in `LogIcon`.
+use super::icons::IconSet;
+use super::tables::FIGURES;
+
+/// The figure with the given name, drawn with the given set
+pub fn find(name: &str, set: IconSet) -> Option<&'static str> {
+    let name = name.to_lowercase();
+
+    FIGURES
+        .iter()
+        .find(|(figure, _, _)| *figure == name)
+        .map(|(_, unicode, ascii)| match set {
+            IconSet::Unicode => *unicode,
+            IconSet::Ascii => *ascii,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_figures() {
+        assert_eq!(find("arrow right", IconSet::Unicode), Some("→"));
+        assert_eq!(find("Arrow Right", IconSet::Ascii), Some("->"));
+        assert_eq!(find("ellipsis", IconSet::Ascii), Some("..."));
+        assert_eq!(find("arrow", IconSet::Unicode), None);
+    }
+
+    #[test]
+    fn ascii_is_ascii() {
+        for (name, _, ascii) in FIGURES {
+            assert!(ascii.is_ascii(), "{} isn't ascii", name);
+        }
+    }
+}
@@ -2,13 +2,177 @@
 //! outputting to the terminal. All icons are printable
 //! and can be converted to strings.
 //!
-//! There are no tags that can be used
-//! in log strings on the other hand. So you can't
-//! write `<info>` in a string and expect it to
-//! be replaced with the info icon.
-use super::keys::FromKey;
+//! Every icon is also a key, `<info>` in a log
+//! string is replaced with the info icon.
+//!
+//! Terminals that can't show these can get plain
+//! ASCII versions instead, see [`set_icon_set`](fn.set_icon_set.html).
+use super::keys::{FromKey, Key};
+use std::env;
 use std::fmt::{Display, Formatter, Result as DisplayResult};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::RwLock;
+
+/// Set icons are drawn with, `UNDECIDED` until it's set or detected
+static ICON_SET: AtomicU8 = AtomicU8::new(UNDECIDED);
+
+const UNDECIDED: u8 = 0;
+const UNICODE: u8 = 1;
+const ASCII: u8 = 2;
+
+/// Icons added at runtime, by their cleaned up name
+static CUSTOM_ICONS: RwLock<Vec<(String, String)>> = RwLock::new(Vec::new());
+
+/// The characters icons are drawn with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IconSet {
+    /// `ℹ ✖ ⚠ ✔ ♥`, used unless the locale says otherwise
+    Unicode,
+
+    /// `i x ! v <3`, for terminals that can't show
+    /// anything else, like some Windows consoles
+    Ascii,
+}
+
+impl IconSet {
+    /// Picks a set based on the locale in `LC_ALL`, `LC_CTYPE` or `LANG`,
+    /// whichever is set first. Anything that isn't UTF-8 gets ASCII.
+    /// Without a locale, only old Windows consoles get ASCII.
+    /// Icons go by this unless [`set_icon_set`](fn.set_icon_set.html) says otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use paris::formatter::{set_icon_set, IconSet};
+    ///
+    /// set_icon_set(IconSet::detect());
+    /// ```
+    pub fn detect() -> Self {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty());
+
+        // Windows Terminal sets this and handles unicode just fine
+        let modern_console = !cfg!(windows) || env::var_os("WT_SESSION").is_some();
+
+        Self::from_locale(locale.as_deref(), modern_console)
+    }
+
+    fn from_locale(locale: Option<&str>, modern_console: bool) -> Self {
+        let locale = match locale {
+            Some(locale) => locale.to_lowercase(),
+            None if modern_console => return IconSet::Unicode,
+            None => return IconSet::Ascii,
+        };
+
+        if locale.contains("utf-8") || locale.contains("utf8") {
+            return IconSet::Unicode;
+        }
+
+        IconSet::Ascii
+    }
+}
+
+/// Sets the characters all icons are drawn with from now on,
+/// both in tags like `<info>` and when displaying a [`LogIcon`](enum.LogIcon.html).
+/// Without this the set is picked by [`IconSet::detect`](enum.IconSet.html#method.detect)
+/// the first time an icon is drawn.
+///
+/// # Example
+/// ```
+/// use paris::formatter::{colorize_string, set_icon_set, IconSet};
+///
+/// set_icon_set(IconSet::Ascii);
+/// assert_eq!(colorize_string("<tick> done"), "v done");
+/// # set_icon_set(IconSet::Unicode);
+/// ```
+pub fn set_icon_set(set: IconSet) {
+    ICON_SET.store(encode(set), Ordering::Relaxed);
+}
+
+/// Adds an icon that can be used as a key everywhere from now on,
+/// in the logger, the formatter and the macros. Adding one with the
+/// name of an existing icon replaces it.
+///
+/// # Example
+/// ```
+/// use paris::formatter::{add_icon, colorize_string};
+///
+/// add_icon("rocket", "🚀");
+/// assert_eq!(colorize_string("<rocket> Launched"), "🚀 Launched");
+/// ```
+pub fn add_icon(name: &str, icon: &str) {
+    let name = clean_name(name);
+    let mut icons = CUSTOM_ICONS.write().unwrap_or_else(|e| e.into_inner());
+
+    match icons.iter_mut().find(|(existing, _)| *existing == name) {
+        Some((_, existing)) => *existing = icon.to_string(),
+        None => icons.push((name, icon.to_string())),
+    }
+}
+
+/// An icon added with [`add_icon`](fn.add_icon.html), by its cleaned up key
+pub(crate) fn custom_icon(name: &str) -> Option<String> {
+    let name = name.to_lowercase();
+
+    CUSTOM_ICONS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(existing, _)| *existing == name)
+        .map(|(_, icon)| icon.clone())
+}
+
+/// Same cleanup keys go through, so `my-icon` is found as `<my_icon>` too
+pub(super) fn clean_name(name: &str) -> String {
+    Key::new(name).name().to_lowercase()
+}
+
+/// The characters icons are currently drawn with, detected
+/// from the locale if nothing was set before
+pub fn icon_set() -> IconSet {
+    match ICON_SET.load(Ordering::Relaxed) {
+        UNICODE => IconSet::Unicode,
+        ASCII => IconSet::Ascii,
+        _ => {
+            let detected = encode(IconSet::detect());
+
+            // Something set in the meantime wins over the detected one
+            let set = match ICON_SET.compare_exchange(
+                UNDECIDED,
+                detected,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => detected,
+                Err(set) => set,
+            };
+
+            match set {
+                ASCII => IconSet::Ascii,
+                _ => IconSet::Unicode,
+            }
+        }
+    }
+}
+
+/// Draws icons with the given set until the guard is dropped,
+/// so tests that check icons pass whatever locale they run in
+#[cfg(test)]
+pub(crate) fn pin_icon_set(set: IconSet) -> std::sync::MutexGuard<'static, ()> {
+    let lock = crate::output::lock_settings();
+    set_icon_set(set);
+
+    lock
+}
+
+fn encode(set: IconSet) -> u8 {
+    match set {
+        IconSet::Unicode => UNICODE,
+        IconSet::Ascii => ASCII,
+    }
+}
 
 /// Contains definitions for icons that can be
 /// used in the terminal. See [this github repo](https://github.com/sindresorhus/figures)
@@ -70,17 +234,42 @@ pub enum LogIcon {
 }
 
 impl LogIcon {
-    /// Match the enum value and return the equivalent icon.
+    /// Match the enum value and return the equivalent icon,
+    /// drawn with whatever [`icon_set`](fn.icon_set.html) is active.
     /// See [this github repo](https://github.com/sindresorhus/figures)
     /// for all icons
     pub fn to_str<'a>(&self) -> &'a str {
-        match self {
-            LogIcon::Info => "ℹ",
-            LogIcon::Cross => "✖",
-            LogIcon::Warning => "⚠",
-            LogIcon::Tick => "✔",
-            LogIcon::Heart => "♥",
-            LogIcon::None => "",
+        self.in_set(icon_set())
+    }
+
+    /// Same as [`to_str`](#method.to_str) but always
+    /// drawn with the given set
+    ///
+    /// # Example
+    /// ```
+    /// use paris::formatter::IconSet;
+    /// use paris::LogIcon;
+    ///
+    /// assert_eq!(LogIcon::Heart.in_set(IconSet::Ascii), "<3");
+    /// ```
+    pub fn in_set<'a>(&self, set: IconSet) -> &'a str {
+        match set {
+            IconSet::Unicode => match self {
+                LogIcon::Info => "ℹ",
+                LogIcon::Cross => "✖",
+                LogIcon::Warning => "⚠",
+                LogIcon::Tick => "✔",
+                LogIcon::Heart => "♥",
+                LogIcon::None => "",
+            },
+            IconSet::Ascii => match self {
+                LogIcon::Info => "i",
+                LogIcon::Cross => "x",
+                LogIcon::Warning => "!",
+                LogIcon::Tick => "v",
+                LogIcon::Heart => "<3",
+                LogIcon::None => "",
+            },
         }
     }
 }
@@ -106,7 +295,7 @@ impl FromStr for LogIcon {
         match s.as_ref() {
             "info" => Ok(LogIcon::Info),
             "cross" => Ok(LogIcon::Cross),
-            "warn" => Ok(LogIcon::Warning),
+            "warn" | "warning" => Ok(LogIcon::Warning),
             "tick" => Ok(LogIcon::Tick),
             "heart" => Ok(LogIcon::Heart),
             _ => Err(()),
@@ -133,6 +322,7 @@ mod tests {
         ($name:ident, $value:expr) => {
             #[test]
             fn $name() {
+                let _icons = pin_icon_set(IconSet::Unicode);
                 let v = String::from(stringify!($name));
                 let c = LogIcon::from_key(&v).unwrap();
 
@@ -146,4 +336,49 @@ mod tests {
     icon_test!(info, "ℹ");
     icon_test!(warn, "⚠");
     icon_test!(heart, "♥");
+
+    #[test]
+    fn names_match_table() {
+        for name in crate::formatter::tables::LOG_ICONS {
+            assert!(LogIcon::from_key(name).is_some(), "{} isn't an icon", name);
+        }
+    }
+
+    #[test]
+    fn ascii() {
+        let icons = [
+            LogIcon::Info,
+            LogIcon::Cross,
+            LogIcon::Warning,
+            LogIcon::Tick,
+            LogIcon::Heart,
+        ];
+
+        let ascii: Vec<&str> = icons.iter().map(|i| i.in_set(IconSet::Ascii)).collect();
+
+        assert_eq!(ascii, vec!["i", "x", "!", "v", "<3"]);
+    }
+
+    #[test]
+    fn custom_icons() {
+        add_icon("test-rocket", "🚀");
+        assert_eq!(custom_icon("test rocket").unwrap(), "🚀");
+
+        add_icon("TEST_ROCKET", "R");
+        assert_eq!(custom_icon("test rocket").unwrap(), "R");
+
+        assert!(custom_icon("test").is_none());
+    }
+
+    #[test]
+    fn detect_from_locale() {
+        let detect = IconSet::from_locale;
+
+        assert_eq!(detect(Some("en_US.UTF-8"), true), IconSet::Unicode);
+        assert_eq!(detect(Some("de_DE.utf8"), false), IconSet::Unicode);
+        assert_eq!(detect(Some("C"), true), IconSet::Ascii);
+        assert_eq!(detect(Some("en_US.ISO-8859-1"), true), IconSet::Ascii);
+        assert_eq!(detect(None, true), IconSet::Unicode);
+        assert_eq!(detect(None, false), IconSet::Ascii);
+    }
 }
@@ -1,7 +1,11 @@
 use crate::formatter::color::Color;
-use crate::formatter::icons::LogIcon;
+use crate::formatter::emoji::Emoji;
+use crate::formatter::figures;
+use crate::formatter::icons::{self, LogIcon};
 use crate::formatter::keys::FromKey;
+use crate::formatter::renderer::Resolved;
 use crate::formatter::style::Style;
+use crate::formatter::tables;
 use std::fmt::{Display, Formatter, Result};
 
 pub struct Key<'a> {
@@ -13,7 +17,7 @@ impl<'a> Key<'a> {
     pub fn new(value: &'a str) -> Self {
         Self {
             contents: value,
-            clean: Self::clean(value),
+            clean: tables::clean(value),
         }
     }
 
@@ -21,6 +25,23 @@ impl<'a> Key<'a> {
         self.contents
     }
 
+    /// The key without its brackets and separators
+    pub fn name(&self) -> &str {
+        &self.clean
+    }
+
+    /// Name of the key this one closes, `red` for `</red>`.
+    /// The reset keys (`</>`, `<//>`, `<///>`) don't close anything.
+    pub fn closes(&self) -> Option<&str> {
+        let name = self.clean.strip_prefix('/')?;
+
+        if name.is_empty() || name.starts_with('/') {
+            return None;
+        }
+
+        Some(name)
+    }
+
     pub fn as_color(&self) -> Option<String> {
         Color::from_key(&self.clean)
     }
@@ -29,47 +50,51 @@ impl<'a> Key<'a> {
         Style::from_key(&self.clean)
     }
 
+    /// Icons added at runtime come first so
+    /// they can replace the built in ones
     pub fn as_icon(&self) -> Option<String> {
-        LogIcon::from_key(&self.clean)
+        icons::custom_icon(&self.clean)
+            .or_else(|| LogIcon::from_key(&self.clean))
+            .or_else(|| figures::find(&self.clean, icons::icon_set()).map(String::from))
     }
 
-    pub fn to_ansi(&self) -> String {
-        let mut content: String = self.contents().to_owned();
+    /// A few colors and styles in one key, like `<bold red on-black>`
+    /// or `<red+bold>`, combined into a single escape sequence.
+    /// Every part has to be a color or style for this to work.
+    pub fn as_compound(&self) -> Option<String> {
+        tables::compound(&self.clean).map(|codes| format!("\x1B[{}m", codes))
+    }
 
-        if let Some(c) = self.as_color() {
-            content = c;
-        }
+    /// Emoji shortcodes, `<:rocket:>` or `<emoji-rocket>`. These go
+    /// by what was written since cleaning would mess up `<:-1:>`.
+    pub fn as_emoji(&self) -> Option<String> {
+        Emoji::from_key(self.contents)
+    }
 
-        if let Some(s) = self.as_style() {
-            content = s;
+    /// Whatever the key stands for, if it stands for anything
+    pub fn resolve(&self) -> Option<Resolved> {
+        if let Some(icon) = self.as_icon().or_else(|| self.as_emoji()) {
+            return Some(Resolved::Text(icon));
         }
 
-        if let Some(i) = self.as_icon() {
-            content = i;
+        let ansi = self
+            .as_style()
+            .or_else(|| self.as_color())
+            .or_else(|| self.as_compound())?;
+
+        if matches!(self.clean.as_str(), "/" | "//" | "///") {
+            return Some(Resolved::Reset(ansi));
         }
 
-        content
+        Some(Resolved::Style(ansi))
     }
 
-    /// Removes characters that can be used instead
-    /// of spaces from a key if the key doesn't already
-    /// contain spaces
-    fn clean(key: &str) -> String {
-        let key = key.trim_matches(|c| c == '<' || c == '>');
-
-        // If key already contains space, its already
-        // intended or a typo
-        if key.contains(' ') {
-            return key.to_string();
-        }
-
-        key.chars()
-            .map(|c| match c {
-                '_' => ' ',
-                '-' => ' ',
-                _ => c,
-            })
-            .collect()
+    /// Whatever the key turns into, nothing if it doesn't stand for anything
+    #[cfg(not(feature = "no_logger"))]
+    pub fn to_ansi(&self) -> String {
+        self.resolve()
+            .map(Resolved::into_string)
+            .unwrap_or_default()
     }
 }
 
@@ -82,13 +107,44 @@ impl Display for Key<'_> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::formatter::{pin_icon_set, IconSet};
 
     #[test]
     fn cleanup() {
         let color = "<on_bright-green>";
 
-        let clean = Key::clean(color);
+        let clean = tables::clean(color);
 
         assert_eq!("on bright green", clean);
     }
+
+    #[test]
+    fn figures() {
+        let _icons = pin_icon_set(IconSet::Unicode);
+        assert_eq!(Key::new("<arrow-right>").as_icon().unwrap(), "→");
+        assert_eq!(Key::new("<pointer_small>").as_icon().unwrap(), "›");
+        assert_eq!(Key::new("<warning>").as_icon().unwrap(), "⚠");
+        assert!(Key::new("<arrow>").as_icon().is_none());
+    }
+
+    #[test]
+    fn compound() {
+        let compound = |key| Key::new(key).as_compound();
+
+        assert_eq!(compound("<bold red on-black>").unwrap(), "\x1B[1;31;40m");
+        assert_eq!(compound("<red+bold>").unwrap(), "\x1B[31;1m");
+        assert_eq!(compound("<u on bright blue>").unwrap(), "\x1B[4;104m");
+        assert_eq!(compound("<on-bright-red+i>").unwrap(), "\x1B[101;3m");
+    }
+
+    #[test]
+    fn not_compound() {
+        let compound = |key| Key::new(key).as_compound();
+
+        assert!(compound("<red>").is_none());
+        assert!(compound("<on bright green>").is_none());
+        assert!(compound("<html attr>").is_none());
+        assert!(compound("<red bold info>").is_none());
+        assert!(compound("<red /bold>").is_none());
+    }
 }
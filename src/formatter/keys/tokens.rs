@@ -0,0 +1,213 @@
+use super::Key;
+
+/// A piece of the input, either plain text
+/// that should be written as is, or a key
+pub enum Token<'a> {
+    Text(&'a str),
+    Key(Key<'a>),
+}
+
+/// Splits the input into text and keys in a single pass
+/// without allocating anything along the way.
+pub struct Tokens<'a> {
+    input: &'a str,
+
+    #[cfg(not(feature = "no_logger"))]
+    len: usize,
+}
+
+impl<'a> Tokens<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+
+            #[cfg(not(feature = "no_logger"))]
+            len: input.len(),
+        }
+    }
+
+    /// Byte offset of the next token in the original input
+    #[cfg(not(feature = "no_logger"))]
+    pub fn offset(&self) -> usize {
+        self.len - self.input.len()
+    }
+
+    /// Returns the length of the key at the start of the
+    /// input, if there is a proper one there. Something like
+    /// `<---- <red>` is not a key until the second `<`.
+    fn key_length(&self) -> Option<usize> {
+        for (idx, c) in self.input.char_indices().skip(1) {
+            match c {
+                '>' => return Some(idx + 1),
+                '<' => return None,
+                _ => (),
+            }
+        }
+
+        None
+    }
+}
+
+/// Whether the input starts with `<<` followed by something that looks
+/// like a key. Anything else, like `a << b` or `<<EOF`, is left alone.
+fn escapes_key(input: &str) -> bool {
+    let rest = input.trim_start_matches('<');
+
+    input.len() - rest.len() >= 2 && opens_key(rest)
+}
+
+/// Whether the text right after a `<` looks like the inside of a key,
+/// something that ends with `>` and doesn't start or end with spaces
+pub fn opens_key(rest: &str) -> bool {
+    match rest.find(&['<', '>'][..]) {
+        Some(end) if rest[end..].starts_with('>') => {
+            let inside = &rest[..end];
+            !inside.is_empty() && inside.trim() == inside
+        }
+        _ => false,
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.input.is_empty() {
+            return None;
+        }
+
+        // Escaped opening, `<<red>` is written out as `<red>`
+        if escapes_key(self.input) {
+            let (text, rest) = self.input.split_at(2);
+            self.input = rest;
+
+            return Some(Token::Text(&text[..1]));
+        }
+
+        if self.input.starts_with('<') {
+            if let Some(len) = self.key_length() {
+                let (key, rest) = self.input.split_at(len);
+                self.input = rest;
+
+                return Some(Token::Key(Key::new(key)));
+            }
+        }
+
+        // Everything up until the next '<' is just text. A false
+        // opening counts as text as well so skip over it.
+        let skip = if self.input.starts_with('<') { 1 } else { 0 };
+        let end = self.input[skip..]
+            .find('<')
+            .map(|i| i + skip)
+            .unwrap_or_else(|| self.input.len());
+
+        let (text, rest) = self.input.split_at(end);
+        self.input = rest;
+
+        Some(Token::Text(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(input: &str) -> Vec<String> {
+        Tokens::new(input)
+            .map(|t| match t {
+                Token::Text(t) => format!("text:{}", t),
+                Token::Key(k) => format!("key:{}", k),
+            })
+            .collect()
+    }
+
+    fn key_count(input: &str) -> usize {
+        Tokens::new(input)
+            .filter(|t| matches!(t, Token::Key(_)))
+            .count()
+    }
+
+    #[test]
+    fn find_keys() {
+        let input = "<black> <red> one two <three>";
+
+        assert_eq!(key_count(input), 3);
+    }
+
+    #[test]
+    fn ignore_fake_keys() {
+        let input = "<black><-------------------- some text <some random opening here, <and another here </>";
+
+        assert_eq!(key_count(input), 2);
+    }
+
+    #[test]
+    fn mess_around() {
+        let input = "<< powering on 'TV' (0)";
+        let _keys = key_count(input);
+
+        let input = "<< something that doesn't end after weird patterns < alksdfa < ngi2oueng <<ikdoqlksmads <black></>";
+
+        assert_eq!(key_count(input), 2);
+    }
+
+    #[test]
+    fn escaped_openings() {
+        let tokens = collect("<<red> <<<b>x");
+
+        assert_eq!(
+            tokens,
+            vec!["text:<", "text:red> ", "text:<", "key:<b>", "text:x"]
+        );
+    }
+
+    #[test]
+    fn unescaped_double_openings() {
+        assert_eq!(collect("a << b"), vec!["text:a ", "text:<", "text:< b"]);
+        assert_eq!(collect("<<EOF"), vec!["text:<", "text:<EOF"]);
+        assert_eq!(
+            collect("a << b > c"),
+            vec!["text:a ", "text:<", "key:< b >", "text: c"]
+        );
+        assert_eq!(collect("<<>"), vec!["text:<", "key:<>"]);
+    }
+
+    #[test]
+    fn text_and_keys() {
+        let tokens = collect("one <red>two</> three");
+
+        assert_eq!(
+            tokens,
+            vec![
+                "text:one ",
+                "key:<red>",
+                "text:two",
+                "key:</>",
+                "text: three"
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_logger"))]
+    fn offsets() {
+        let mut tokens = Tokens::new("ab<red>ℹ<</>");
+        let mut offsets = vec![tokens.offset()];
+
+        while tokens.next().is_some() {
+            offsets.push(tokens.offset());
+        }
+
+        assert_eq!(offsets, vec![0, 2, 7, 10, 12, 14]);
+    }
+
+    #[test]
+    fn false_openings_are_text() {
+        let tokens = collect("<---- <red>ℹ <unterminated");
+
+        assert_eq!(
+            tokens,
+            vec!["text:<---- ", "key:<red>", "text:ℹ ", "text:<unterminated"]
+        );
+    }
+}
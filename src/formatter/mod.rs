@@ -3,21 +3,47 @@
 
 mod color;
 mod concerns;
+mod emoji;
+mod figures;
 mod icons;
 mod keys;
+mod renderer;
+mod sanitize;
 mod style;
+pub(crate) mod tables;
+pub(crate) mod width;
+
+#[cfg(feature = "macros")]
+mod pieces;
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub use pieces::{write_pieces, Arg, Piece, Recorder};
 
 #[cfg(not(feature = "no_logger"))]
 mod custom;
 #[cfg(not(feature = "no_logger"))]
+mod validate;
+#[cfg(not(feature = "no_logger"))]
+use crate::Theme;
+#[cfg(not(feature = "no_logger"))]
 use custom::CustomStyle;
 #[cfg(not(feature = "no_logger"))]
 use keys::Key;
+#[cfg(not(feature = "no_logger"))]
+pub use validate::{Diagnostic, DiagnosticKind};
 
-use keys::KeyList;
+use renderer::Resolved;
+use std::fmt;
 
 pub use concerns::Ansi;
-pub use icons::LogIcon;
+pub use icons::{add_icon, icon_set, set_icon_set, IconSet, LogIcon};
+
+#[cfg(test)]
+pub(crate) use icons::pin_icon_set;
+pub use sanitize::{sanitize, Sanitize};
+#[cfg(not(feature = "no_logger"))]
+pub use width::Wrap;
+pub use width::{center, pad, truncate_with_ellipsis, visible_width, Align};
 
 /// Heavier formatter that allows the possibility of
 /// custom styles in strings. That is the only reason
@@ -25,17 +51,12 @@ pub use icons::LogIcon;
 /// just use the `colorize_string()` function provided
 /// in the module.
 #[cfg(not(feature = "no_logger"))]
+#[derive(Default)]
 pub struct Formatter<'a> {
     custom_styles: Vec<CustomStyle<'a>>,
-}
-
-#[cfg(not(feature = "no_logger"))]
-impl<'a> Default for Formatter<'a> {
-    fn default() -> Self {
-        Self {
-            custom_styles: vec![],
-        }
-    }
+    icons: Vec<(String, String)>,
+    sanitize: Sanitize,
+    plain_emoji: bool,
 }
 
 #[cfg(not(feature = "no_logger"))]
@@ -49,6 +70,9 @@ impl<'a> Formatter<'a> {
     /// and what colors that style equates to so it knows
     /// what to replace it with when formatting
     ///
+    /// Styles can be made out of other custom styles as well.
+    /// Defining a style that already exists replaces it.
+    ///
     /// # Example
     /// ```
     /// use paris::formatter::Formatter;
@@ -57,8 +81,137 @@ impl<'a> Formatter<'a> {
     /// fmt.new_style("lol", vec!["green", "bold", "on_blue"]);
     ///
     /// // '<lol>' is now a key that can be used in strings
+    /// fmt.new_style("lmao", vec!["lol", "underline"]);
+    ///
+    /// // '<lmao>' is everything '<lol>' is, and underlined
+    /// ```
     pub fn new_style(&mut self, key: &str, colors: Vec<&'a str>) -> &mut Self {
-        self.custom_styles.push(CustomStyle::new(key, colors));
+        self.add_style(CustomStyle::new(key, colors))
+    }
+
+    /// Adds every style from the theme, replacing
+    /// any existing ones with the same name
+    ///
+    /// # Example
+    /// ```
+    /// use paris::formatter::Formatter;
+    /// use paris::Theme;
+    ///
+    /// let theme: Theme = "lol = green bold".parse().unwrap();
+    /// let mut fmt = Formatter::new();
+    /// fmt.theme(&theme);
+    ///
+    /// assert_eq!(fmt.style_names(), vec!["lol"]);
+    /// ```
+    pub fn theme(&mut self, theme: &Theme) -> &mut Self {
+        for (key, colors) in theme.styles() {
+            self.add_style(CustomStyle::new(key, colors.to_vec()));
+        }
+
+        self
+    }
+
+    /// Adds the style, or replaces the one with the same key
+    fn add_style(&mut self, style: CustomStyle<'a>) -> &mut Self {
+        match self
+            .custom_styles
+            .iter_mut()
+            .find(|s| s.key() == style.key())
+        {
+            Some(existing) => *existing = style,
+            None => self.custom_styles.push(style),
+        }
+
+        self
+    }
+
+    /// Adds an icon only this formatter knows about, it comes
+    /// before any other icon with the same name. Use
+    /// [`add_icon`](fn.add_icon.html) for one that works everywhere.
+    ///
+    /// # Example
+    /// ```
+    /// use paris::formatter::Formatter;
+    ///
+    /// let mut fmt = Formatter::new();
+    /// fmt.add_icon("rocket", "🚀");
+    ///
+    /// assert_eq!(fmt.colorize("<rocket> Launched"), "🚀 Launched");
+    /// ```
+    pub fn add_icon(&mut self, name: &str, icon: &str) -> &mut Self {
+        let name = icons::clean_name(name);
+
+        match self
+            .icons
+            .iter_mut()
+            .find(|(existing, _)| *existing == name)
+        {
+            Some((_, existing)) => *existing = icon.to_string(),
+            None => self.icons.push((name, icon.to_string())),
+        }
+
+        self
+    }
+
+    /// Forget about a style, its key won't be replaced anymore.
+    /// Styles built on top of it lose whatever it added.
+    ///
+    /// # Example
+    /// ```
+    /// use paris::formatter::Formatter;
+    ///
+    /// let mut fmt = Formatter::new();
+    /// fmt.new_style("lol", vec!["green"]).remove_style("lol");
+    ///
+    /// assert!(fmt.style_names().is_empty());
+    /// ```
+    pub fn remove_style(&mut self, key: &str) -> &mut Self {
+        let key = format!("<{}>", key);
+        self.custom_styles.retain(|style| style.key() != key);
+
+        self
+    }
+
+    /// Names of all custom styles, in the order they were first defined
+    pub fn style_names(&self) -> Vec<&str> {
+        self.custom_styles.iter().map(CustomStyle::name).collect()
+    }
+
+    /// Tell the formatter what to do with control characters and
+    /// raw escape sequences found in the text it formats. The ones
+    /// it generates itself for keys are always kept.
+    ///
+    /// # Example
+    /// ```
+    /// use paris::formatter::{Formatter, Sanitize};
+    ///
+    /// let mut fmt = Formatter::new();
+    /// fmt.sanitize(Sanitize::Strip);
+    ///
+    /// assert_eq!(fmt.colorize("<red>\x1B[2Jhi</>"), "\x1B[31mhi\x1B[0m");
+    /// ```
+    pub fn sanitize(&mut self, mode: Sanitize) -> &mut Self {
+        self.sanitize = mode;
+
+        self
+    }
+
+    /// Turn emoji shortcodes like `<:rocket:>` on or off. When off,
+    /// they're written as plain text instead, `<3` for `<:heart:>` or
+    /// the shortcode itself if there's nothing better.
+    ///
+    /// # Example
+    /// ```
+    /// use paris::formatter::Formatter;
+    ///
+    /// let mut fmt = Formatter::new();
+    /// assert_eq!(fmt.colorize("<:rocket:> <:heart:>"), "🚀 ❤️");
+    ///
+    /// fmt.emoji(false);
+    /// assert_eq!(fmt.colorize("<:rocket:> <:heart:>"), ":rocket: <3");
+    /// ```
+    pub fn emoji(&mut self, enabled: bool) -> &mut Self {
+        self.plain_emoji = !enabled;
 
         self
     }
@@ -68,30 +221,156 @@ impl<'a> Formatter<'a> {
     /// And replaces all those keys with their color, style
     /// or icon equivalent.
     pub fn colorize(&self, input: &str) -> String {
-        let mut output = input.to_string();
+        let mut output = String::with_capacity(input.len());
+
+        // Writing to a string can't fail
+        let _ = self.colorize_to(input, &mut output);
+        output
+    }
+
+    /// Same as [`colorize`](#method.colorize) but writes
+    /// straight into the given target instead of allocating
+    /// a new string.
+    ///
+    /// # Example
+    /// ```
+    /// use paris::formatter::Formatter;
+    ///
+    /// let fmt = Formatter::new();
+    /// let mut output = String::new();
+    ///
+    /// fmt.colorize_to("<red>Written in place</>", &mut output).unwrap();
+    /// ```
+    pub fn colorize_to<W>(&self, input: &str, out: &mut W) -> fmt::Result
+    where
+        W: fmt::Write + ?Sized,
+    {
+        renderer::render(input, self.sanitize, out, |key| self.resolve(key))
+    }
 
-        for key in KeyList::new(&input) {
-            if let Some(style) = self.as_style(&key) {
-                let ansi = style.expand();
-                output = output.replace(&key.to_string(), &ansi);
+    /// Checks the given input for keys that won't turn into
+    /// anything when colorizing. Unknown tags, closing tags with
+    /// nothing to close and things that were probably meant to be keys.
+    /// Custom styles count as known tags, unless they end up including themselves.
+    ///
+    /// # Example
+    /// ```
+    /// use paris::formatter::{DiagnosticKind, Formatter};
+    ///
+    /// let fmt = Formatter::new();
+    /// let diagnostics = fmt.validate("<bule>Typo</>");
+    ///
+    /// assert_eq!(diagnostics[0].kind, DiagnosticKind::UnknownTag);
+    /// assert_eq!(diagnostics[0].span, 0..6);
+    /// ```
+    pub fn validate(&self, input: &str) -> Vec<Diagnostic> {
+        validate::validate(
+            input,
+            |key| self.resolve(key),
+            |key| match self.as_style(key) {
+                Some(style) => {
+                    let mut looped = false;
+                    self.expand(style, &mut vec![], &mut looped);
+
+                    looped
+                }
+                None => false,
+            },
+        )
+    }
+
+    /// Whatever the key stands for, custom icons and styles first
+    fn resolve(&self, key: &Key) -> Option<Resolved> {
+        if self.plain_emoji {
+            if let Some(text) = emoji::Emoji::fallback(key.contents()) {
+                return Some(Resolved::Text(text));
             }
+        }
+
+        let name = key.name().to_lowercase();
 
-            output = output.replace(&key.to_string(), &key.to_ansi());
+        if let Some((_, icon)) = self.icons.iter().find(|(existing, _)| *existing == name) {
+            return Some(Resolved::Text(icon.clone()));
         }
 
-        output
+        match self.as_style(key) {
+            Some(style) => Some(Resolved::Style(self.expand(style, &mut vec![], &mut false))),
+            None => key.resolve(),
+        }
+    }
+
+    /// Turns a custom style into its escape sequences, going
+    /// through every style it's built on. A style that shows up
+    /// again while it's still being expanded is skipped, so styles
+    /// that end up including themselves don't loop forever, and
+    /// `looped` is set. Colors that don't exist (anymore) add nothing.
+    fn expand<'s>(
+        &'s self,
+        style: &'s CustomStyle<'a>,
+        expanding: &mut Vec<&'s str>,
+        looped: &mut bool,
+    ) -> String {
+        expanding.push(style.key());
+
+        let ansi = style.expand(
+            |color| match self.as_style(&Key::new(&format!("<{}>", color))) {
+                Some(parent) if expanding.contains(&parent.key()) => {
+                    *looped = true;
+                    String::new()
+                }
+                Some(parent) => self.expand(parent, expanding, looped),
+                None => color.to_ansi(),
+            },
+        );
+
+        expanding.pop();
+        ansi
     }
 
     /// Convert a key to a custom style if they match
-    fn as_style(&self, key: &Key) -> Option<&CustomStyle> {
-        for style in self.custom_styles.iter() {
-            if style.key() == key.contents() {
-                return Some(style);
-            }
+    fn as_style(&self, key: &Key) -> Option<&CustomStyle<'_>> {
+        self.custom_styles
+            .iter()
+            .find(|style| style.key() == key.contents())
+    }
+}
+
+/// Escapes every key in the given input so it's written out
+/// as is instead of being replaced. Use this on anything
+/// you don't control before putting it in a log.
+///
+/// A key is escaped by doubling its opening, `<<red>` is
+/// written out as `<red>`. Any other `<` is left as it is.
+///
+/// # Example
+/// ```
+/// use paris::formatter::{colorize_string, escape};
+///
+/// let input = "<red>not actually red</>";
+/// let output = colorize_string(format!("<blue>{}</>", escape(input)));
+///
+/// assert!(output.contains(input));
+/// ```
+pub fn escape(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(idx) = rest.find('<') {
+        output.push_str(&rest[..idx]);
+
+        let after = rest[idx..].trim_start_matches('<');
+        let openings = &rest[idx..rest.len() - after.len()];
+        output.push_str(openings);
+
+        if keys::opens_key(after) {
+            output.push_str(openings);
         }
 
-        None
+        rest = after;
     }
+
+    output.push_str(rest);
+    output
 }
 
 /// Finds all keys in the given input. If with_colors
@@ -105,19 +384,36 @@ where
     S: Into<String>,
 {
     let input = input.into();
-    let mut output = input.clone();
-    let empty = "";
+    let mut output = String::with_capacity(input.len());
 
-    for key in KeyList::new(&input) {
+    // Writing to a string can't fail
+    let _ = write_string(&input, with_colors, &mut output);
+    output
+}
+
+/// Same as [`format_string`](fn.format_string.html) but writes
+/// straight into the given target instead of allocating a new string.
+///
+/// #### This function does not take into account custom styles, you need the struct for that.
+///
+/// # Example
+/// ```
+/// use paris::formatter::write_string;
+///
+/// let mut output = String::new();
+/// write_string("<blue>Written in place</>", true, &mut output).unwrap();
+/// ```
+pub fn write_string<W>(input: &str, with_colors: bool, out: &mut W) -> fmt::Result
+where
+    W: fmt::Write + ?Sized,
+{
+    renderer::render(input, Sanitize::Off, out, |key| {
         if with_colors {
-            output = output.replace(&key.to_string(), &key.to_ansi());
-            continue;
+            return key.resolve();
         }
 
-        output = output.replace(&key.to_string(), empty);
-    }
-
-    output
+        Some(Resolved::Text(String::new()))
+    })
 }
 
 /// Finds all keys in the given input. Keys meaning
@@ -214,7 +510,7 @@ mod tests {
         let s = format!("{}Test string", k);
         let parsed = colorize_string(s);
 
-        assert!(!parsed.contains(&k));
+        assert!(!parsed.contains(k));
         assert!(parsed.contains(&c));
     }
 
@@ -227,6 +523,28 @@ mod tests {
         assert!(parsed.contains("<html>"));
     }
 
+    #[test]
+    fn escaped_keys() {
+        let parsed = colorize_string("<<red> Vec<<T> <<</>");
+
+        assert_eq!(parsed, "<red> Vec<T> <\x1B[0m");
+    }
+
+    #[test]
+    fn escape_roundtrip() {
+        let input = "<red>Vec<T></> << <b <<i> a << b <<EOF";
+
+        assert_eq!(format_string(escape(input), false), input);
+        assert_eq!(colorize_string(escape(input)), input);
+    }
+
+    #[test]
+    fn double_openings_without_keys() {
+        assert_eq!(colorize_string("a << b"), "a << b");
+        assert_eq!(colorize_string("cat <<EOF"), "cat <<EOF");
+        assert_eq!(escape("a << b"), "a << b");
+    }
+
     #[test]
     fn no_colors() {
         let s = String::from("<bright_green>Something is black</>");
@@ -239,6 +557,40 @@ mod tests {
         assert_eq!(parsed, expected);
     }
 
+    #[test]
+    fn closing_tags() {
+        let parsed = colorize_string("<red>a <bold>b</bold> c</red>");
+
+        assert_eq!(parsed, "\x1B[31ma \x1B[1mb\x1B[0m\x1B[31m c\x1B[0m");
+
+        let unmatched = colorize_string("</b><red>a</red> b");
+        assert_eq!(unmatched, "\x1B[22m\x1B[31ma\x1B[0m b");
+    }
+
+    #[test]
+    fn color_resets() {
+        assert_eq!(colorize_string("<red>a<//>b"), "\x1B[31ma\x1B[39mb");
+        assert_eq!(
+            colorize_string("<b><on-red>a<///>b"),
+            "\x1B[1m\x1B[41ma\x1B[49mb\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn unclosed_tags_reset() {
+        let _icons = pin_icon_set(IconSet::Unicode);
+        assert_eq!(colorize_string("<on-red>a"), "\x1B[41ma\x1B[0m");
+        assert_eq!(colorize_string("<tick> a"), "✔ a");
+    }
+
+    #[test]
+    fn look_alike_text_is_left_alone() {
+        let _icons = pin_icon_set(IconSet::Unicode);
+        let parsed = colorize_string("<info> shouldn't break because of <i");
+
+        assert_eq!(parsed, "ℹ shouldn't break because of <i");
+    }
+
     #[test]
     #[cfg(not(feature = "no_logger"))]
     fn custom_style() {
@@ -254,4 +606,118 @@ mod tests {
         assert!(!parsed.contains("<custom>"));
         assert!(!parsed.contains("<lol>"));
     }
+
+    #[test]
+    #[cfg(not(feature = "no_logger"))]
+    fn validate_custom_style() {
+        let mut fmt = Formatter::new();
+        fmt.new_style("lol", vec!["cyan", "on-blue"]);
+
+        assert!(fmt.validate("<lol>a</lol>").is_empty());
+        assert_eq!(fmt.validate("<lul>a</>").len(), 1);
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_logger"))]
+    fn formatter_icons() {
+        let mut fmt = Formatter::new();
+        fmt.add_icon("only-here", "X").add_icon("tick", "T");
+
+        assert_eq!(fmt.colorize("<only_here> <tick>"), "X T");
+        assert!(fmt.validate("<only-here>").is_empty());
+
+        // Other formatters and the global functions don't know about them
+        assert_eq!(Formatter::new().colorize("<only-here>"), "<only-here>");
+        assert_eq!(colorize_string("<only-here>"), "<only-here>");
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_logger"))]
+    fn inherited_style() {
+        let mut fmt = Formatter::new();
+        fmt.new_style("error-base", vec!["red", "bold"])
+            .new_style("error-strong", vec!["error-base", "underline"]);
+
+        assert_eq!(
+            fmt.colorize("<error-strong>a"),
+            "\x1B[31m\x1B[1m\x1B[4ma\x1B[0m"
+        );
+
+        // Parents are looked up when used, not when defined
+        fmt.new_style("error-base", vec!["yellow"]);
+        assert_eq!(fmt.colorize("<error-strong>a"), "\x1B[33m\x1B[4ma\x1B[0m");
+
+        fmt.remove_style("error-base");
+        assert_eq!(fmt.colorize("<error-strong>a"), "\x1B[4ma\x1B[0m");
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_logger"))]
+    fn style_cycles() {
+        let mut fmt = Formatter::new();
+        fmt.new_style("a", vec!["b", "red"])
+            .new_style("b", vec!["a", "bold"])
+            .new_style("c", vec!["c"]);
+
+        assert_eq!(fmt.colorize("<a>x</a>"), "\x1B[1m\x1B[31mx\x1B[0m");
+        assert_eq!(fmt.colorize("<b>x</b>"), "\x1B[31m\x1B[1mx\x1B[0m");
+        assert_eq!(fmt.colorize("<c>x"), "x");
+
+        let diagnostics = fmt.validate("<red>x</red> <c>y</c> <a>z");
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::StyleCycle);
+        assert_eq!(diagnostics[0].span, 13..16);
+        assert_eq!(diagnostics[1].span, 22..25);
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_logger"))]
+    fn redefined_styles() {
+        let mut fmt = Formatter::new();
+        fmt.new_style("one", vec!["red"])
+            .new_style("two", vec!["blue"])
+            .new_style("one", vec!["green"]);
+
+        assert_eq!(fmt.style_names(), vec!["one", "two"]);
+        assert_eq!(fmt.colorize("<one>x"), "\x1B[32mx\x1B[0m");
+
+        fmt.remove_style("one").remove_style("nothing");
+        assert_eq!(fmt.style_names(), vec!["two"]);
+        assert_eq!(fmt.colorize("<one>x"), "<one>x");
+    }
+
+    #[test]
+    fn emoji_shortcodes() {
+        assert_eq!(
+            colorize_string("<:tada:> <emoji-sparkles> <:nope:>"),
+            "🎉 ✨ <:nope:>"
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_logger"))]
+    fn plain_emoji() {
+        let mut fmt = Formatter::new();
+        fmt.emoji(false);
+
+        assert_eq!(
+            fmt.colorize("<:+1:> <emoji-tada> <:nope:>"),
+            "+1 :tada: <:nope:>"
+        );
+        assert!(fmt.validate("<:tada:>").is_empty());
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_logger"))]
+    fn closing_custom_style() {
+        let mut fmt = Formatter::new();
+        fmt.new_style("lol", vec!["cyan", "on-blue"]);
+
+        let parsed = fmt.colorize("<red>a <lol>b</lol> c</>");
+
+        assert_eq!(
+            parsed,
+            "\x1B[31ma \x1B[36m\x1B[44mb\x1B[0m\x1B[31m c\x1B[0m"
+        );
+    }
 }
@@ -0,0 +1,211 @@
+use super::icons;
+use super::keys::Key;
+use super::renderer::Renderer;
+use super::sanitize::{self, Sanitize};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::fmt::{self, Result, Write};
+use std::ops::Range;
+
+/// Part of a message that the macros already broke
+/// down while compiling. Not meant to be used directly.
+#[doc(hidden)]
+pub enum Piece<'a> {
+    /// Plain text, no keys in here
+    Text(&'a str),
+
+    /// A key that was already turned into its escape sequence
+    Style {
+        /// The key as it was written
+        key: &'a str,
+
+        /// What it turned into
+        ansi: &'a str,
+    },
+
+    /// A key that can only be resolved at runtime, like icons
+    Key(&'a str),
+
+    /// A formatted argument, may have keys of its own
+    Arg(String),
+
+    /// The whole message, formatted at runtime because the format
+    /// string wasn't a literal (like `concat!(...)`)
+    Runtime {
+        /// The formatted message
+        text: String,
+
+        /// Where each argument ended up in the message
+        args: Vec<Range<usize>>,
+    },
+}
+
+/// Formats messages whose format string isn't a literal while
+/// keeping track of where every argument ends up, so they can
+/// still be told apart from the format string once it's all one string.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct Recorder {
+    len: Cell<usize>,
+    args: RefCell<Vec<Range<usize>>>,
+}
+
+impl Recorder {
+    /// Wraps an argument so its position is recorded
+    /// while it's being formatted
+    pub fn arg<'a, T: ?Sized>(&'a self, value: &'a T) -> Arg<'a, T> {
+        Arg {
+            value,
+            recorder: self,
+        }
+    }
+
+    /// Formats the message, the arguments in it should
+    /// all be wrapped by [`arg`](#method.arg)
+    pub fn record(&self, message: fmt::Arguments) -> Piece<'static> {
+        let mut sink = Sink {
+            text: String::new(),
+            len: &self.len,
+        };
+
+        fmt::write(&mut sink, message)
+            .expect("a formatting trait implementation returned an error");
+
+        Piece::Runtime {
+            text: sink.text,
+            args: self.args.take(),
+        }
+    }
+}
+
+/// String that lets the recorder know how long it is
+/// while it's still being written to
+struct Sink<'a> {
+    text: String,
+    len: &'a Cell<usize>,
+}
+
+impl<'a> Write for Sink<'a> {
+    fn write_str(&mut self, s: &str) -> Result {
+        self.text.push_str(s);
+        self.len.set(self.text.len());
+        Ok(())
+    }
+}
+
+/// An argument that records where it was written to
+#[doc(hidden)]
+pub struct Arg<'a, T: ?Sized> {
+    value: &'a T,
+    recorder: &'a Recorder,
+}
+
+/// Forwards each formatting trait to the wrapped
+/// value, with every option left as it was given
+macro_rules! forward {
+    ($($trait:ident),*) => {
+        $(
+            impl<'a, T: fmt::$trait + ?Sized> fmt::$trait for Arg<'a, T> {
+                fn fmt(&self, f: &mut fmt::Formatter) -> Result {
+                    let start = self.recorder.len.get();
+                    fmt::$trait::fmt(self.value, f)?;
+
+                    let end = self.recorder.len.get();
+                    self.recorder.args.borrow_mut().push(start..end);
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+forward!(Display, Debug, LowerHex, UpperHex, Octal, Binary, LowerExp, UpperExp, Pointer);
+
+/// Writes every piece to the given target, resolving whatever
+/// is left to resolve, with `before` and `after` around them.
+/// Arguments are sanitized with `sanitize_args`, and written
+/// as they are if `verbatim_args` is true.
+#[doc(hidden)]
+pub fn write_pieces<W>(
+    (before, after): (&str, &str),
+    pieces: &[Piece],
+    (verbatim_args, sanitize_args): (bool, Sanitize),
+    out: &mut W,
+) -> Result
+where
+    W: Write + ?Sized,
+{
+    let mut renderer = Renderer::new(out, Sanitize::Off);
+    let mut resolve = |key: &Key| key.resolve();
+
+    renderer.render(before, &mut resolve)?;
+
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => renderer.text(text)?,
+            // Icons added at runtime replace built in keys, same as they do in the formatter
+            Piece::Style { key, .. } if icons::custom_icon(Key::new(key).name()).is_some() => {
+                renderer.key(&Key::new(key), &mut resolve)?
+            }
+            Piece::Style { key, ansi } => renderer.style(Key::new(key).name(), ansi)?,
+            Piece::Key(key) => renderer.key(&Key::new(key), &mut resolve)?,
+            Piece::Arg(arg) => {
+                let arg = sanitize(arg, sanitize_args);
+
+                if verbatim_args {
+                    renderer.text(&arg)?;
+                } else {
+                    renderer.render(&arg, &mut resolve)?;
+                }
+            }
+            Piece::Runtime { text, args } => {
+                let parts = split_runtime(text, args, sanitize_args);
+
+                if !verbatim_args {
+                    let text: String = parts.iter().map(|(part, _)| part.as_ref()).collect();
+                    renderer.render(&text, &mut resolve)?;
+                    continue;
+                }
+
+                for (part, is_arg) in parts {
+                    if is_arg {
+                        renderer.text(&part)?;
+                    } else {
+                        renderer.render(&part, &mut resolve)?;
+                    }
+                }
+            }
+        }
+    }
+
+    renderer.render(after, &mut resolve)?;
+    renderer.finish()
+}
+
+/// Splits a message formatted at runtime back into the format
+/// string and its arguments, the arguments already sanitized
+fn split_runtime<'a>(
+    text: &'a str,
+    args: &[Range<usize>],
+    mode: Sanitize,
+) -> Vec<(Cow<'a, str>, bool)> {
+    let mut parts = vec![];
+    let mut at = 0;
+
+    for arg in args {
+        parts.push((Cow::Borrowed(&text[at..arg.start]), false));
+        parts.push((sanitize(&text[arg.clone()], mode), true));
+        at = arg.end;
+    }
+
+    parts.push((Cow::Borrowed(&text[at..]), false));
+    parts
+}
+
+/// The text as is if there's nothing to sanitize
+fn sanitize(text: &str, mode: Sanitize) -> Cow<'_, str> {
+    match mode {
+        Sanitize::Off => Cow::Borrowed(text),
+        _ => Cow::Owned(sanitize::sanitize(text, mode)),
+    }
+}
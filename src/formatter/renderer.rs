@@ -0,0 +1,317 @@
+use super::concerns::Ansi;
+use super::keys::{Key, Token, Tokens};
+use super::sanitize::{self, Sanitize};
+use std::fmt::{Result, Write};
+
+const RESET: &str = "\x1B[0m";
+
+/// What a key turned into
+pub enum Resolved {
+    /// Changes how everything after it looks, colors and styles
+    Style(String),
+
+    /// Resets everything back to default with `</>`,
+    /// or only the colors with `<//>` and `<///>`
+    Reset(String),
+
+    /// Anything else, like icons
+    Text(String),
+}
+
+impl Resolved {
+    #[cfg(not(feature = "no_logger"))]
+    pub fn into_string(self) -> String {
+        match self {
+            Resolved::Style(s) | Resolved::Reset(s) | Resolved::Text(s) => s,
+        }
+    }
+}
+
+/// A style that's currently active
+pub struct Open {
+    pub name: String,
+    pub ansi: String,
+}
+
+/// Finds the latest open style matching the closing key, either
+/// by name or by what it resolves to (`</b>` closes `<bold>`)
+pub fn find_open<F>(open: &[Open], name: &str, resolve: &mut F) -> Option<usize>
+where
+    F: FnMut(&Key) -> Option<Resolved>,
+{
+    let name = name.to_lowercase();
+
+    if let Some(at) = open.iter().rposition(|o| o.name == name) {
+        return Some(at);
+    }
+
+    let opening = format!("<{}>", name);
+
+    match resolve(&Key::new(&opening)) {
+        Some(Resolved::Style(ansi)) => open.iter().rposition(|o| o.ansi == ansi),
+        _ => None,
+    }
+}
+
+/// Forgets every open style the reset turns off, all of them
+/// for `</>`, only the ones that are nothing but colors otherwise
+pub fn reset(open: &mut Vec<Open>, ansi: &str) {
+    if ansi == RESET {
+        open.clear();
+        return;
+    }
+
+    open.retain(|o| Ansi::leaves_style(&format!("{}{}", o.ansi, ansi)) != Some(false));
+}
+
+/// Writes text and keys to the target while keeping track of
+/// every style that's still open, so closing one of them brings
+/// back exactly what was there before it.
+pub struct Renderer<'w, W: ?Sized> {
+    out: &'w mut W,
+    mode: Sanitize,
+    open: Vec<Open>,
+
+    /// Whether escape sequences that were already in
+    /// the text left some style active
+    raw_style: bool,
+}
+
+impl<'w, W> Renderer<'w, W>
+where
+    W: Write + ?Sized,
+{
+    pub fn new(out: &'w mut W, mode: Sanitize) -> Self {
+        Self {
+            out,
+            mode,
+            open: vec![],
+            raw_style: false,
+        }
+    }
+
+    /// Walks through the input once, writing text as is and
+    /// whatever `resolve` returns for each key. Keys that don't
+    /// resolve to anything are written back untouched.
+    ///
+    /// Text, and keys that don't resolve, are sanitized
+    /// on the way out.
+    pub fn render<F>(&mut self, input: &str, mut resolve: F) -> Result
+    where
+        F: FnMut(&Key) -> Option<Resolved>,
+    {
+        for token in Tokens::new(input) {
+            match token {
+                Token::Text(text) => self.text(text)?,
+                Token::Key(key) => self.key(&key, &mut resolve)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes plain text, sanitized
+    pub fn text(&mut self, text: &str) -> Result {
+        if self.mode == Sanitize::Off {
+            if let Some(active) = Ansi::leaves_style(text) {
+                self.raw_style = active;
+            }
+        }
+
+        sanitize::write(text, self.mode, self.out)
+    }
+
+    /// Writes a single key. Closing keys like `</red>` go back
+    /// to whatever was active before the matching `<red>`.
+    pub fn key<F>(&mut self, key: &Key, resolve: &mut F) -> Result
+    where
+        F: FnMut(&Key) -> Option<Resolved>,
+    {
+        if let Some(name) = key.closes() {
+            if let Some(at) = find_open(&self.open, name, resolve) {
+                return self.close(at);
+            }
+        }
+
+        match resolve(key) {
+            // Closing something that isn't open, like `</b>` without a `<b>`,
+            // only resets that one thing and doesn't open anything itself
+            Some(Resolved::Style(ansi)) if key.closes().is_some() => self.out.write_str(&ansi),
+            Some(Resolved::Style(ansi)) => self.style(key.name(), &ansi),
+            Some(Resolved::Reset(ansi)) => {
+                reset(&mut self.open, &ansi);
+
+                if ansi == RESET {
+                    self.raw_style = false;
+                }
+
+                self.out.write_str(&ansi)
+            }
+            Some(Resolved::Text(text)) => self.out.write_str(&text),
+            None => self.text(key.contents()),
+        }
+    }
+
+    /// Writes a style that was already resolved and remembers it
+    pub fn style(&mut self, name: &str, ansi: &str) -> Result {
+        // Keys that resolve to nothing (no colors) don't count
+        if !ansi.is_empty() {
+            self.open.push(Open {
+                name: name.to_lowercase(),
+                ansi: ansi.to_string(),
+            });
+        }
+
+        self.out.write_str(ansi)
+    }
+
+    /// Resets everything if there's still something active,
+    /// so nothing bleeds into whatever comes after
+    pub fn finish(self) -> Result {
+        if self.open.is_empty() && !self.raw_style {
+            return Ok(());
+        }
+
+        self.out.write_str(RESET)
+    }
+
+    /// Drops everything from `at` onwards and brings back what's left
+    fn close(&mut self, at: usize) -> Result {
+        self.open.truncate(at);
+        self.raw_style = false;
+        self.out.write_str(RESET)?;
+
+        for open in self.open.iter() {
+            self.out.write_str(&open.ansi)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders the whole input in one go, see `Renderer::render`
+pub fn render<W, F>(input: &str, mode: Sanitize, out: &mut W, resolve: F) -> Result
+where
+    W: Write + ?Sized,
+    F: FnMut(&Key) -> Option<Resolved>,
+{
+    let mut renderer = Renderer::new(out, mode);
+    renderer.render(input, resolve)?;
+    renderer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn styled(key: &Key) -> Option<Resolved> {
+        match key.contents() {
+            "</>" => Some(Resolved::Reset(String::from(RESET))),
+            "<//>" => Some(Resolved::Reset(String::from("\x1B[39m"))),
+            "<i>" => Some(Resolved::Text(String::from("I"))),
+            k if k.starts_with("</") => None,
+            k => Some(Resolved::Style(
+                k.trim_matches(|c| c == '<' || c == '>').to_uppercase(),
+            )),
+        }
+    }
+
+    fn rendered(input: &str) -> String {
+        let mut output = String::new();
+        render(input, Sanitize::Off, &mut output, styled).unwrap();
+
+        output
+    }
+
+    #[test]
+    fn unresolved_keys_stay() {
+        let mut output = String::new();
+        render("<a> and <b>", Sanitize::Off, &mut output, |_| None).unwrap();
+
+        assert_eq!(output, "<a> and <b>");
+    }
+
+    #[test]
+    fn replaces_in_place() {
+        let mut output = String::new();
+        render("<a> and <b>", Sanitize::Off, &mut output, |k| {
+            Some(Resolved::Text(k.contents().to_uppercase()))
+        })
+        .unwrap();
+
+        assert_eq!(output, "<A> and <B>");
+    }
+
+    #[test]
+    fn closing_restores_outer_styles() {
+        assert_eq!(rendered("<a>1<b>2</b>3</a>"), "A1B2\x1B[0mA3\x1B[0m");
+    }
+
+    #[test]
+    fn closing_skips_everything_above() {
+        assert_eq!(rendered("<a><b><c>1</b>2</>"), "ABC1\x1B[0mA2\x1B[0m");
+    }
+
+    #[test]
+    fn unmatched_closing_is_left_alone() {
+        assert_eq!(rendered("<a>1</b>"), "A1</b>\x1B[0m");
+    }
+
+    #[test]
+    fn unmatched_closing_is_not_kept_open() {
+        let mut output = String::new();
+        render("</x><a>1</a>2", Sanitize::Off, &mut output, |k| {
+            Some(Resolved::Style(k.contents().to_string()))
+        })
+        .unwrap();
+
+        assert_eq!(output, "</x><a>1\x1B[0m2");
+    }
+
+    #[test]
+    fn color_resets_forget_colors() {
+        let mut output = String::new();
+        let styles = |key: &Key| match key.contents() {
+            "<//>" => Some(Resolved::Reset(String::from("\x1B[39m"))),
+            "<red>" => Some(Resolved::Style(String::from("\x1B[31m"))),
+            "<b>" => Some(Resolved::Style(String::from("\x1B[1m"))),
+            _ => None,
+        };
+
+        render("<b><red>1<//>2</b>3", Sanitize::Off, &mut output, styles).unwrap();
+        assert_eq!(output, "\x1B[1m\x1B[31m1\x1B[39m2\x1B[0m3");
+
+        output.clear();
+        render("<red>1<//>2<b>3</red>4", Sanitize::Off, &mut output, styles).unwrap();
+        assert_eq!(output, "\x1B[31m1\x1B[39m2\x1B[1m3</red>4\x1B[0m");
+    }
+
+    #[test]
+    fn unclosed_styles_reset_at_the_end() {
+        assert_eq!(rendered("<a>1"), "A1\x1B[0m");
+        assert_eq!(rendered("<a>1</>"), "A1\x1B[0m");
+        assert_eq!(rendered("<i> 1"), "I 1");
+    }
+
+    #[test]
+    fn raw_styles_reset_at_the_end() {
+        assert_eq!(rendered("\x1B[31m1"), "\x1B[31m1\x1B[0m");
+        assert_eq!(rendered("\x1B[31m1\x1B[0m"), "\x1B[31m1\x1B[0m");
+        assert_eq!(rendered("\x1B[31m1</>"), "\x1B[31m1\x1B[0m");
+    }
+
+    #[test]
+    fn resolved_keys_are_not_sanitized() {
+        let mut output = String::new();
+        render("\x1B[2J<a><b\x07>", Sanitize::Strip, &mut output, |k| {
+            if k.contents() == "<a>" {
+                return Some(Resolved::Text(String::from("\x1B[1m")));
+            }
+
+            None
+        })
+        .unwrap();
+
+        assert_eq!(output, "\x1B[1m<b>");
+    }
+}
@@ -0,0 +1,163 @@
+use std::fmt::{Result, Write};
+
+/// What to do with control characters and raw escape
+/// sequences found in the text of a message. The escapes
+/// paris writes for keys are never touched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Sanitize {
+    /// Leave everything as is. This is the default.
+    #[default]
+    Off,
+
+    /// Remove control characters, along with whole escape
+    /// sequences (colors, cursor movement, OSC commands, etc.)
+    Strip,
+
+    /// Write control characters out as visible `\x1b`
+    /// style escapes so they do nothing to the terminal
+    Escape,
+}
+
+/// Sanitizes the given input
+///
+/// # Example
+/// ```
+/// use paris::formatter::{sanitize, Sanitize};
+///
+/// let input = "\x1B[2Jcleared your screen";
+///
+/// assert_eq!(sanitize(input, Sanitize::Strip), "cleared your screen");
+/// assert_eq!(sanitize(input, Sanitize::Escape), "\\x1b[2Jcleared your screen");
+/// ```
+pub fn sanitize(input: &str, mode: Sanitize) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    // Writing to a string can't fail
+    let _ = write(input, mode, &mut output);
+    output
+}
+
+/// Writes the text to the given target, sanitized according to `mode`
+pub(crate) fn write<W>(text: &str, mode: Sanitize, out: &mut W) -> Result
+where
+    W: Write + ?Sized,
+{
+    match mode {
+        Sanitize::Off => out.write_str(text),
+        Sanitize::Strip => strip(text, out),
+        Sanitize::Escape => escape(text, out),
+    }
+}
+
+/// Newlines and tabs are fine, everything else can mess with the terminal
+fn is_unsafe(c: char) -> bool {
+    c.is_control() && c != '\n' && c != '\t'
+}
+
+fn strip<W>(text: &str, out: &mut W) -> Result
+where
+    W: Write + ?Sized,
+{
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if !is_unsafe(c) {
+            out.write_char(c)?;
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            // Control sequence, ends with a byte between '@' and '~'
+            ('\x1B', Some('[')) | ('\u{9B}', _) => {
+                if c == '\x1B' {
+                    chars.next();
+                }
+
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+
+            // OSC and other strings, end with BEL or ESC \
+            ('\x1B', Some(']'))
+            | ('\x1B', Some('P'))
+            | ('\x1B', Some('X'))
+            | ('\x1B', Some('^'))
+            | ('\x1B', Some('_')) => {
+                chars.next();
+
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+
+                    if c == '\x1B' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+
+            // Anything else that starts with ESC is a single character
+            ('\x1B', Some(_)) => {
+                chars.next();
+            }
+
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+fn escape<W>(text: &str, out: &mut W) -> Result
+where
+    W: Write + ?Sized,
+{
+    for c in text.chars() {
+        if is_unsafe(c) {
+            write!(out, "\\x{:02x}", c as u32)?;
+            continue;
+        }
+
+        out.write_char(c)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn off() {
+        let input = "\x1B[31mred\x07";
+
+        assert_eq!(sanitize(input, Sanitize::Off), input);
+    }
+
+    #[test]
+    fn strip_sequences() {
+        let input = "a\x1B[1;31mb\x1B]0;title\x07c\x1B]8;;http://x\x1B\\d\x1B7e\u{9B}2Jf\rg\x08h";
+
+        assert_eq!(sanitize(input, Sanitize::Strip), "abcdefgh");
+    }
+
+    #[test]
+    fn keeps_newlines_and_tabs() {
+        let input = "one\n\ttwo";
+
+        assert_eq!(sanitize(input, Sanitize::Strip), input);
+        assert_eq!(sanitize(input, Sanitize::Escape), input);
+    }
+
+    #[test]
+    fn escape_visibly() {
+        let input = "\x1B[2J\r\u{9B}";
+
+        assert_eq!(sanitize(input, Sanitize::Escape), "\\x1b[2J\\x0d\\x9b");
+    }
+}
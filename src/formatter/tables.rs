@@ -0,0 +1,257 @@
+//! Every built in key paris knows about, and the few helpers
+//! needed to read them. The macros crate links to this exact file
+//! (`macros/src/tables.rs`) so the format strings it parses while
+//! compiling end up the same as anything parsed at runtime.
+//!
+//! Nothing in here can depend on the rest of either crate.
+
+/// Styles, closing keys included, and their codes
+pub const STYLES: &[(&str, u8)] = &[
+    ("bold", 1),
+    ("b", 1),
+    ("/bold", 22),
+    ("/b", 22),
+    ("dimmed", 2),
+    ("d", 2),
+    ("/dimmed", 22),
+    ("/d", 22),
+    ("italic", 3),
+    ("i", 3),
+    ("/italic", 23),
+    ("/i", 23),
+    ("underline", 4),
+    ("u", 4),
+    ("/underline", 24),
+    ("/u", 24),
+    ("blink", 5),
+    ("l", 5),
+    ("/blink", 25),
+    ("/l", 25),
+    ("reverse", 7),
+    ("r", 7),
+    ("/reverse", 27),
+    ("/r", 27),
+    ("hidden", 8),
+    ("h", 8),
+    ("/hidden", 28),
+    ("/h", 28),
+    ("strikethrough", 9),
+    ("s", 9),
+    ("/strikethrough", 29),
+    ("/s", 29),
+];
+
+/// Colors and their foreground codes, the
+/// background version is always 10 more
+pub const COLORS: &[(&str, u8)] = &[
+    ("black", 30),
+    ("red", 31),
+    ("green", 32),
+    ("yellow", 33),
+    ("blue", 34),
+    ("magenta", 35),
+    ("cyan", 36),
+    ("white", 37),
+    ("bright black", 90),
+    ("bright red", 91),
+    ("bright green", 92),
+    ("bright yellow", 93),
+    ("bright blue", 94),
+    ("bright magenta", 95),
+    ("bright cyan", 96),
+    ("bright white", 97),
+];
+
+/// Different resets for each color or everything, the same
+/// with or without `on` in front
+pub const RESETS: &[(&str, u8)] = &[("///", 49), ("//", 39), ("/", 0)];
+
+/// Names of the icons in `LogIcon`, only
+/// the macros need them on their own
+#[allow(dead_code)]
+pub const LOG_ICONS: &[&str] = &["info", "cross", "warn", "warning", "tick", "heart"];
+
+/// Name as it looks after cleaning the key, the icon, and its ASCII version
+pub const FIGURES: &[(&str, &str, &str)] = &[
+    ("square", "█", "#"),
+    ("square small", "◻", "[ ]"),
+    ("square small filled", "◼", "[#]"),
+    ("square dark shade", "▓", "#"),
+    ("square medium shade", "▒", "#"),
+    ("square light shade", "░", "."),
+    ("square top", "▀", "\""),
+    ("square bottom", "▄", "_"),
+    ("square left", "▌", "["),
+    ("square right", "▐", "]"),
+    ("square center", "■", "#"),
+    ("circle", "◯", "( )"),
+    ("circle filled", "◉", "(*)"),
+    ("circle dotted", "◌", "( )"),
+    ("circle double", "◎", "(o)"),
+    ("circle circle", "ⓞ", "(o)"),
+    ("circle cross", "ⓧ", "(x)"),
+    ("circle pipe", "Ⓘ", "(|)"),
+    ("circle question mark", "?⃝", "(?)"),
+    ("radio on", "◉", "(*)"),
+    ("radio off", "◯", "( )"),
+    ("checkbox on", "☒", "[x]"),
+    ("checkbox off", "☐", "[ ]"),
+    ("checkbox circle on", "ⓧ", "(x)"),
+    ("checkbox circle off", "Ⓘ", "( )"),
+    ("question mark prefix", "?⃝", "?"),
+    ("bullet", "●", "*"),
+    ("dot", "․", "."),
+    ("ellipsis", "…", "..."),
+    ("pointer", "❯", ">"),
+    ("pointer small", "›", ">"),
+    ("triangle up", "▲", "^"),
+    ("triangle down", "▼", "v"),
+    ("triangle left", "◀", "<"),
+    ("triangle right", "▶", ">"),
+    ("lozenge", "◆", "<>"),
+    ("lozenge outline", "◇", "<>"),
+    ("home", "⌂", "~"),
+    ("hamburger", "☰", "="),
+    ("smiley", "㋡", ":)"),
+    ("mustache", "෴", "~"),
+    ("star", "★", "*"),
+    ("play", "▶", ">"),
+    ("music note", "♪", "#"),
+    ("music note beamed", "♫", "##"),
+    ("nodejs", "⬢", "<>"),
+    ("arrow up", "↑", "^"),
+    ("arrow down", "↓", "v"),
+    ("arrow left", "←", "<-"),
+    ("arrow right", "→", "->"),
+    ("arrow left right", "↔", "<->"),
+    ("arrow up down", "↕", "|"),
+    ("almost equal", "≈", "~="),
+    ("not equal", "≠", "!="),
+    ("less or equal", "≤", "<="),
+    ("greater or equal", "≥", ">="),
+    ("identical", "≡", "=="),
+    ("infinity", "∞", "inf"),
+    ("subscript zero", "₀", "0"),
+    ("subscript one", "₁", "1"),
+    ("subscript two", "₂", "2"),
+    ("subscript three", "₃", "3"),
+    ("subscript four", "₄", "4"),
+    ("subscript five", "₅", "5"),
+    ("subscript six", "₆", "6"),
+    ("subscript seven", "₇", "7"),
+    ("subscript eight", "₈", "8"),
+    ("subscript nine", "₉", "9"),
+    ("one half", "½", "1/2"),
+    ("one third", "⅓", "1/3"),
+    ("one quarter", "¼", "1/4"),
+    ("one fifth", "⅕", "1/5"),
+    ("one sixth", "⅙", "1/6"),
+    ("one seventh", "⅐", "1/7"),
+    ("one eighth", "⅛", "1/8"),
+    ("one ninth", "⅑", "1/9"),
+    ("one tenth", "⅒", "1/10"),
+    ("two thirds", "⅔", "2/3"),
+    ("two fifths", "⅖", "2/5"),
+    ("three quarters", "¾", "3/4"),
+    ("three fifths", "⅗", "3/5"),
+    ("three eighths", "⅜", "3/8"),
+    ("four fifths", "⅘", "4/5"),
+    ("five sixths", "⅚", "5/6"),
+    ("five eighths", "⅝", "5/8"),
+    ("seven eighths", "⅞", "7/8"),
+    ("line", "─", "-"),
+    ("line bold", "━", "="),
+    ("line double", "═", "="),
+    ("line vertical", "│", "|"),
+    ("line vertical bold", "┃", "|"),
+    ("line vertical double", "║", "|"),
+];
+
+/// Code of the style with the given (cleaned up) name
+pub fn style(key: &str) -> Option<u8> {
+    let key = key.to_lowercase();
+
+    STYLES
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, code)| *code)
+}
+
+/// Code of the color with the given (cleaned up) name,
+/// `on` in front of it makes it a background color
+pub fn color(key: &str) -> Option<u8> {
+    let is_bg = key.starts_with("on");
+    let key = key.trim_start_matches("on ").to_lowercase();
+
+    if let Some((_, code)) = RESETS.iter().find(|(name, _)| *name == key) {
+        return Some(*code);
+    }
+
+    let (_, code) = COLORS.iter().find(|(name, _)| *name == key)?;
+
+    if is_bg {
+        return Some(code + 10);
+    }
+
+    Some(*code)
+}
+
+/// Removes the brackets, and characters that can be used instead
+/// of spaces, from a key. Keys that already contain spaces only
+/// lose the brackets, the spaces were either intended or a typo.
+pub fn clean(key: &str) -> String {
+    let key = key.trim_matches(|c| c == '<' || c == '>');
+
+    if key.contains(' ') {
+        return key.to_string();
+    }
+
+    key.chars()
+        .map(|c| match c {
+            '_' | '-' => ' ',
+            _ => c,
+        })
+        .collect()
+}
+
+/// A few colors and styles in one (cleaned up) key, like `bold red on black`
+/// or `red+bold`, as the codes to put in a single escape sequence,
+/// `1;31;40` and `31;1`. Every part has to be a color or style.
+pub fn compound(key: &str) -> Option<String> {
+    let words: Vec<String> = key
+        .split(|c: char| c.is_whitespace() || c == '+')
+        .flat_map(|part| part.split(['-', '_']))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    if words.len() < 2 || words.iter().any(|word| word.starts_with('/')) {
+        return None;
+    }
+
+    let mut codes = vec![];
+    let mut at = 0;
+
+    // Longest match first so `on bright red` isn't read as `on`, `bright`, `red`
+    'words: while at < words.len() {
+        for len in (1..=3.min(words.len() - at)).rev() {
+            let part = words[at..at + len].join(" ");
+
+            if let Some(code) = style(&part).or_else(|| color(&part)) {
+                codes.push(code.to_string());
+                at += len;
+
+                continue 'words;
+            }
+        }
+
+        return None;
+    }
+
+    // A single part is just a normal key written differently
+    if codes.len() < 2 {
+        return None;
+    }
+
+    Some(codes.join(";"))
+}
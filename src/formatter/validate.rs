@@ -0,0 +1,220 @@
+use super::keys::{Key, Token, Tokens};
+use super::renderer::{self, Open, Resolved};
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+
+/// What's wrong with a key
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// Not a color, style, icon or custom style. Probably a typo, like `<bule>`
+    UnknownTag,
+
+    /// A closing key with nothing open for it to close
+    MismatchedClose,
+
+    /// Looks like it was meant to be a key but isn't one, like `<red` or `<>`
+    Malformed,
+
+    /// A custom style that ends up including itself, like `<c>`
+    /// defined as `["c"]`. The parts that loop add nothing.
+    StyleCycle,
+}
+
+/// A problem with one of the keys in a string
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// What's wrong with it
+    pub kind: DiagnosticKind,
+
+    /// The key as it was written
+    pub key: String,
+
+    /// Where the key is in the input, in bytes
+    pub span: Range<usize>,
+}
+
+impl Diagnostic {
+    fn new(kind: DiagnosticKind, key: &str, start: usize) -> Self {
+        Self {
+            kind,
+            key: key.to_string(),
+            span: start..start + key.len(),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let problem = match self.kind {
+            DiagnosticKind::UnknownTag => "unknown tag",
+            DiagnosticKind::MismatchedClose => "nothing to close for",
+            DiagnosticKind::Malformed => "malformed key",
+            DiagnosticKind::StyleCycle => "style includes itself in",
+        };
+
+        write!(
+            f,
+            "{} {} at {}..{}",
+            problem, self.key, self.span.start, self.span.end
+        )
+    }
+}
+
+/// Goes through the input the same way the renderer
+/// would and reports every key that it wouldn't know what to do with.
+/// `loops` tells which styles end up including themselves.
+pub fn validate<F, L>(input: &str, mut resolve: F, mut loops: L) -> Vec<Diagnostic>
+where
+    F: FnMut(&Key) -> Option<Resolved>,
+    L: FnMut(&Key) -> bool,
+{
+    let mut diagnostics = vec![];
+    let mut open: Vec<Open> = vec![];
+    let mut tokens = Tokens::new(input);
+
+    loop {
+        let start = tokens.offset();
+
+        let key = match tokens.next() {
+            Some(Token::Key(key)) => key,
+            Some(Token::Text(text)) => {
+                if let Some(opening) = false_opening(&input[start..], text) {
+                    diagnostics.push(Diagnostic::new(DiagnosticKind::Malformed, opening, start));
+                }
+
+                continue;
+            }
+            None => break,
+        };
+
+        let diagnostic = |kind| Diagnostic::new(kind, key.contents(), start);
+
+        if let Some(name) = key.closes() {
+            if let Some(at) = renderer::find_open(&open, name, &mut resolve) {
+                open.truncate(at);
+                continue;
+            }
+
+            // Either a typo or closing something that was never opened
+            let opening = format!("<{}>", name);
+
+            if resolve(&key).is_none() && resolve(&Key::new(&opening)).is_none() {
+                diagnostics.push(diagnostic(DiagnosticKind::UnknownTag));
+            } else {
+                diagnostics.push(diagnostic(DiagnosticKind::MismatchedClose));
+            }
+
+            continue;
+        }
+
+        match resolve(&key) {
+            Some(Resolved::Style(ansi)) => {
+                if loops(&key) {
+                    diagnostics.push(diagnostic(DiagnosticKind::StyleCycle));
+                }
+
+                open.push(Open {
+                    name: key.name().to_lowercase(),
+                    ansi,
+                })
+            }
+            Some(Resolved::Reset(ansi)) => renderer::reset(&mut open, &ansi),
+            Some(Resolved::Text(_)) => (),
+            None if key.name().trim().is_empty() => {
+                diagnostics.push(diagnostic(DiagnosticKind::Malformed))
+            }
+            None => diagnostics.push(diagnostic(DiagnosticKind::UnknownTag)),
+        }
+    }
+
+    diagnostics
+}
+
+/// Text that starts with something like `<red` but never closes
+/// it. Escaped openings and things like `<--` or `< 3` don't count.
+fn false_opening<'a>(source: &str, text: &'a str) -> Option<&'a str> {
+    if source.starts_with("<<") {
+        return None;
+    }
+
+    let name = text.strip_prefix('<')?;
+
+    if !name.starts_with(|c: char| c.is_alphanumeric() || c == '/') {
+        return None;
+    }
+
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    Some(&text[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(input: &str) -> Vec<(DiagnosticKind, Range<usize>)> {
+        validate(input, |key| key.resolve(), |_| false)
+            .into_iter()
+            .map(|d| (d.kind, d.span))
+            .collect()
+    }
+
+    #[test]
+    fn valid_input() {
+        assert!(kinds("<red>a <b>b</b> c</red> <info> <<bule> <-- < 3</>").is_empty());
+    }
+
+    #[test]
+    fn unknown_tags() {
+        assert_eq!(
+            kinds("<bule>a</bule>"),
+            vec![
+                (DiagnosticKind::UnknownTag, 0..6),
+                (DiagnosticKind::UnknownTag, 7..14)
+            ]
+        );
+    }
+
+    #[test]
+    fn mismatched_closes() {
+        assert_eq!(
+            kinds("<red><b>a</red></b>"),
+            vec![(DiagnosticKind::MismatchedClose, 15..19)]
+        );
+        assert_eq!(
+            kinds("a</italic>"),
+            vec![(DiagnosticKind::MismatchedClose, 1..10)]
+        );
+    }
+
+    #[test]
+    fn malformed_keys() {
+        assert_eq!(
+            kinds("<> <red text <blue>ℹ <b"),
+            vec![
+                (DiagnosticKind::Malformed, 0..2),
+                (DiagnosticKind::Malformed, 3..7),
+                (DiagnosticKind::Malformed, 23..25)
+            ]
+        );
+    }
+
+    #[test]
+    fn style_cycles() {
+        let diagnostics = validate(
+            "<red>a</red> <c>b",
+            |key| key.resolve().or(Some(Resolved::Style(String::new()))),
+            |key| key.contents() == "<c>",
+        );
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::StyleCycle);
+        assert_eq!(diagnostics[0].span, 13..16);
+    }
+
+    #[test]
+    fn display() {
+        let diagnostics = validate("hi <bule>", |key| key.resolve(), |_| false);
+
+        assert_eq!(diagnostics[0].to_string(), "unknown tag <bule> at 3..9");
+    }
+}
@@ -0,0 +1,526 @@
+//! How much room text takes up once it's in the terminal.
+//! Escape sequences take none, most East Asian characters
+//! and emoji take two columns, combining marks take none.
+use super::{colorize_string, Ansi};
+
+/// Where text goes when there's more room than it needs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Align {
+    /// Room goes after the text. This is the default.
+    #[default]
+    Left,
+
+    /// Room goes before the text
+    Right,
+
+    /// Room is split around the text, the extra column goes after
+    Center,
+}
+
+/// Whether long messages get broken up into several lines
+#[cfg(not(feature = "no_logger"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Wrap {
+    /// Lines are as long as they are, the terminal
+    /// breaks them wherever. This is the default.
+    #[default]
+    Off,
+
+    /// Wrap at the width of the terminal, see
+    /// [`terminal_width`](../output/fn.terminal_width.html) for how
+    /// it's found. Nothing is wrapped if it can't be, like when
+    /// stdout isn't a terminal, or without the `terminal_size`
+    /// feature when `COLUMNS` isn't set.
+    Terminal,
+
+    /// Wrap at the given amount of columns
+    Width(usize),
+}
+
+#[cfg(not(feature = "no_logger"))]
+impl Wrap {
+    /// Columns to wrap at, if anything gets wrapped
+    pub(crate) fn columns(self) -> Option<usize> {
+        match self {
+            Wrap::Off => None,
+            Wrap::Terminal => crate::output::terminal_width(),
+            Wrap::Width(columns) => Some(columns).filter(|&c| c > 0),
+        }
+    }
+}
+
+/// Columns the text takes up on screen, with keys replaced
+/// and escape sequences left out.
+///
+/// # Example
+/// ```
+/// use paris::formatter::visible_width;
+///
+/// assert_eq!(visible_width("<red>日本</> go"), 7);
+/// ```
+pub fn visible_width(input: &str) -> usize {
+    width(&colorize_string(input))
+}
+
+/// Replaces keys and fills the text up with spaces until it's
+/// `width` columns wide. Text that's already wider stays as is.
+///
+/// # Example
+/// ```
+/// use paris::formatter::{pad, Align};
+///
+/// assert_eq!(pad("<bold>ab</>", 4, Align::Right), "  \x1B[1mab\x1B[0m");
+/// ```
+pub fn pad(input: &str, width: usize, align: Align) -> String {
+    self::align(&colorize_string(input), width, align)
+}
+
+/// Same as [`pad`](fn.pad.html) with `Align::Center`
+pub fn center(input: &str, width: usize) -> String {
+    pad(input, width, Align::Center)
+}
+
+/// Replaces keys and cuts the text down to `width` columns,
+/// ending with `…` if anything was cut. Styles that are still
+/// on after the cut get reset.
+///
+/// # Example
+/// ```
+/// use paris::formatter::truncate_with_ellipsis;
+///
+/// assert_eq!(truncate_with_ellipsis("hello world", 6), "hello…");
+/// assert_eq!(truncate_with_ellipsis("hello", 6), "hello");
+/// ```
+pub fn truncate_with_ellipsis(input: &str, width: usize) -> String {
+    truncate(&colorize_string(input), width)
+}
+
+/// Columns taken up by text that was already rendered,
+/// escape sequences don't count
+pub fn width(rendered: &str) -> usize {
+    let mut width = 0;
+    let mut rest = rendered;
+
+    while let Some(c) = rest.chars().next() {
+        match escape_len(rest) {
+            Some(len) => rest = &rest[len..],
+            None => {
+                width += char_width(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    width
+}
+
+/// Fills the rendered text up with spaces until it's
+/// `width` columns wide. Text that's already wider stays as is.
+pub fn align(rendered: &str, width: usize, align: Align) -> String {
+    let room = width.saturating_sub(self::width(rendered));
+
+    let (before, after) = match align {
+        Align::Left => (0, room),
+        Align::Right => (room, 0),
+        Align::Center => (room / 2, room - room / 2),
+    };
+
+    format!("{}{}{}", " ".repeat(before), rendered, " ".repeat(after))
+}
+
+/// Cuts text that was already rendered down to `width` columns
+pub fn truncate(rendered: &str, width: usize) -> String {
+    if self::width(rendered) <= width {
+        return rendered.to_string();
+    }
+
+    let room = width.saturating_sub(1);
+    let mut taken = 0;
+    let mut out = String::new();
+    let mut rest = rendered;
+
+    while let Some(c) = rest.chars().next() {
+        let len = match escape_len(rest) {
+            Some(len) => len,
+            None if taken + char_width(c) > room => break,
+            None => {
+                taken += char_width(c);
+                c.len_utf8()
+            }
+        };
+
+        out.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+
+    if width > 0 {
+        out.push('…');
+    }
+
+    if Ansi::leaves_style(&out) == Some(true) {
+        out.push_str("\x1B[0m");
+    }
+
+    out
+}
+
+/// Breaks rendered text up into lines no wider than `width`, between
+/// words where possible. Lines after the first start `indent` columns in,
+/// with whatever style was on at the end of the line before.
+pub fn wrap(rendered: &str, width: usize, indent: usize) -> String {
+    let mut wrapper = Wrapper {
+        out: String::new(),
+        active: String::new(),
+        column: 0,
+        fresh: true,
+        width,
+        indent: if indent < width { indent } else { 0 },
+    };
+
+    for (idx, line) in rendered.split('\n').enumerate() {
+        if idx > 0 {
+            wrapper.newline();
+        }
+
+        for (idx, word) in line.split(' ').enumerate() {
+            if idx > 0 {
+                wrapper.space(self::width(word));
+            }
+
+            wrapper.word(word);
+        }
+    }
+
+    wrapper.out
+}
+
+struct Wrapper {
+    out: String,
+    active: String,
+    column: usize,
+    fresh: bool,
+    width: usize,
+    indent: usize,
+}
+
+impl Wrapper {
+    /// Goes to the next line if the upcoming word
+    /// doesn't fit after a space anymore
+    fn space(&mut self, upcoming: usize) {
+        if !self.fresh && self.column + 1 + upcoming > self.width {
+            return self.newline();
+        }
+
+        self.out.push(' ');
+        self.column += 1;
+    }
+
+    /// Writes the word, breaking it up if it's longer than a line
+    fn word(&mut self, word: &str) {
+        let mut rest = word;
+
+        while let Some(c) = rest.chars().next() {
+            if let Some(len) = escape_len(rest) {
+                self.escape(&rest[..len]);
+                rest = &rest[len..];
+                continue;
+            }
+
+            if !self.fresh && self.column + char_width(c) > self.width {
+                self.newline();
+            }
+
+            self.out.push(c);
+            self.column += char_width(c);
+            self.fresh = false;
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    /// Keeps track of styles that are on so they
+    /// can be carried over to the next line
+    fn escape(&mut self, escape: &str) {
+        self.out.push_str(escape);
+
+        if !escape.ends_with('m') {
+            return;
+        }
+
+        match Ansi::leaves_style(escape) {
+            Some(false) => self.active.clear(),
+            _ => self.active.push_str(escape),
+        }
+    }
+
+    fn newline(&mut self) {
+        if !self.active.is_empty() {
+            self.out.push_str("\x1B[0m");
+        }
+
+        self.out.push('\n');
+        self.out.push_str(&" ".repeat(self.indent));
+        self.out.push_str(&self.active);
+        self.column = self.indent;
+        self.fresh = true;
+    }
+}
+
+/// Length in bytes of the escape sequence at the start of
+/// the text, if it starts with one
+pub fn escape_len(text: &str) -> Option<usize> {
+    let rest = text.strip_prefix('\x1B')?;
+
+    let len = match rest.chars().next() {
+        // Control sequence, ends with a byte between '@' and '~'
+        Some('[') => rest[1..]
+            .find(|c: char| ('@'..='~').contains(&c))
+            .map(|end| end + 3)
+            .unwrap_or(text.len()),
+
+        // Commands like OSC, end with BEL or ESC \
+        Some(']') | Some('P') | Some('_') | Some('^') | Some('X') => {
+            let bel = rest.find('\x07').map(|end| end + 2);
+            let st = rest.find("\x1B\\").map(|end| end + 3);
+
+            match (bel, st) {
+                (Some(bel), Some(st)) => bel.min(st),
+                (bel, st) => bel.or(st).unwrap_or(text.len()),
+            }
+        }
+
+        Some(c) => 1 + c.len_utf8(),
+        None => 1,
+    };
+
+    Some(len)
+}
+
+/// Columns a single character takes up
+pub fn char_width(c: char) -> usize {
+    if c.is_control() || is_zero_width(c) {
+        return 0;
+    }
+
+    if is_wide(c) {
+        return 2;
+    }
+
+    1
+}
+
+fn is_zero_width(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0300..=0x036F
+            | 0x0483..=0x0489
+            | 0x0591..=0x05BD
+            | 0x0610..=0x061A
+            | 0x064B..=0x065F
+            | 0x1AB0..=0x1AFF
+            | 0x1DC0..=0x1DFF
+            | 0x200B..=0x200F
+            | 0x2028..=0x202E
+            | 0x2060..=0x2064
+            | 0x20D0..=0x20FF
+            | 0xFE00..=0xFE0F
+            | 0xFE20..=0xFE2F
+            | 0xFEFF
+            | 0x1F3FB..=0x1F3FF
+            | 0xE0000..=0xE0FFF
+    )
+}
+
+fn is_wide(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1100..=0x115F
+            | 0x231A..=0x231B
+            | 0x2329..=0x232A
+            | 0x23E9..=0x23EC
+            | 0x23F0
+            | 0x23F3
+            | 0x25FD..=0x25FE
+            | 0x2614..=0x2615
+            | 0x2648..=0x2653
+            | 0x267F
+            | 0x2693
+            | 0x26A1
+            | 0x26AA..=0x26AB
+            | 0x26BD..=0x26BE
+            | 0x26C4..=0x26C5
+            | 0x26CE
+            | 0x26D4
+            | 0x26EA
+            | 0x26F2..=0x26F3
+            | 0x26F5
+            | 0x26FA
+            | 0x26FD
+            | 0x2705
+            | 0x270A..=0x270B
+            | 0x2728
+            | 0x274C
+            | 0x274E
+            | 0x2753..=0x2755
+            | 0x2757
+            | 0x2795..=0x2797
+            | 0x27B0
+            | 0x27BF
+            | 0x2B1B..=0x2B1C
+            | 0x2B50
+            | 0x2B55
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xA960..=0xA97F
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE10..=0xFE19
+            | 0xFE30..=0xFE6F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x16FE0..=0x16FE4
+            | 0x17000..=0x18CFF
+            | 0x1B000..=0x1B2FF
+            | 0x1F004
+            | 0x1F0CF
+            | 0x1F18E
+            | 0x1F191..=0x1F19A
+            | 0x1F200..=0x1F202
+            | 0x1F210..=0x1F23B
+            | 0x1F240..=0x1F248
+            | 0x1F250..=0x1F251
+            | 0x1F260..=0x1F265
+            | 0x1F300..=0x1F320
+            | 0x1F32D..=0x1F335
+            | 0x1F337..=0x1F37C
+            | 0x1F37E..=0x1F393
+            | 0x1F3A0..=0x1F3CA
+            | 0x1F3CF..=0x1F3D3
+            | 0x1F3E0..=0x1F3F0
+            | 0x1F3F4
+            | 0x1F3F8..=0x1F43E
+            | 0x1F440
+            | 0x1F442..=0x1F4FC
+            | 0x1F4FF..=0x1F53D
+            | 0x1F54B..=0x1F54E
+            | 0x1F550..=0x1F567
+            | 0x1F57A
+            | 0x1F595..=0x1F596
+            | 0x1F5A4
+            | 0x1F5FB..=0x1F64F
+            | 0x1F680..=0x1F6C5
+            | 0x1F6CC
+            | 0x1F6D0..=0x1F6D2
+            | 0x1F6D5..=0x1F6D7
+            | 0x1F6DC..=0x1F6DF
+            | 0x1F6EB..=0x1F6EC
+            | 0x1F6F4..=0x1F6FC
+            | 0x1F7E0..=0x1F7EB
+            | 0x1F7F0
+            | 0x1F90C..=0x1F93A
+            | 0x1F93C..=0x1F945
+            | 0x1F947..=0x1F9FF
+            | 0x1FA70..=0x1FAFF
+            | 0x20000..=0x2FFFD
+            | 0x30000..=0x3FFFD
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_width() {
+        assert_eq!(width("hello"), 5);
+        assert_eq!(width(""), 0);
+        assert_eq!(width("ℹ ✔"), 3);
+    }
+
+    #[test]
+    fn escapes_dont_count() {
+        assert_eq!(width("\x1B[31mred\x1B[0m"), 3);
+        assert_eq!(width("\x1B[1;31;40mb\x1B[0m"), 1);
+        assert_eq!(width("\x1B]8;;https://x.y\x1B\\link\x1B]8;;\x07"), 4);
+        assert_eq!(width("\x1B[31"), 0);
+    }
+
+    #[test]
+    fn wide_characters() {
+        assert_eq!(width("日本語"), 6);
+        assert_eq!(width("한국"), 4);
+        assert_eq!(width("🚀 go"), 5);
+        assert_eq!(width("ｆｕｌｌ"), 8);
+    }
+
+    #[test]
+    fn zero_width_characters() {
+        assert_eq!(width("e\u{301}"), 1);
+        assert_eq!(width("a\u{200B}b"), 2);
+        assert_eq!(width("👍\u{1F3FD}"), 2);
+    }
+
+    #[test]
+    fn tagged_helpers() {
+        assert_eq!(visible_width("<red>red</> and <bold>bold</>"), 12);
+        assert_eq!(visible_width("<<red>"), 5);
+        assert_eq!(visible_width("<tick> ok"), 4);
+        assert_eq!(pad("<green>ok</>", 4, Align::Left), "\x1B[32mok\x1B[0m  ");
+        assert_eq!(center("<green>ok</>", 5), " \x1B[32mok\x1B[0m  ");
+    }
+
+    #[test]
+    fn truncating() {
+        assert_eq!(truncate_with_ellipsis("hello", 5), "hello");
+        assert_eq!(truncate_with_ellipsis("hello", 4), "hel…");
+        assert_eq!(truncate_with_ellipsis("hello", 1), "…");
+        assert_eq!(truncate_with_ellipsis("hello", 0), "");
+        assert_eq!(truncate_with_ellipsis("日本語", 4), "日…");
+        assert_eq!(truncate_with_ellipsis("日本語", 5), "日本…");
+        assert_eq!(
+            truncate_with_ellipsis("<red>hello</> world", 4),
+            "\x1B[31mhel…\x1B[0m"
+        );
+        assert_eq!(
+            truncate_with_ellipsis("<red>he</>llo", 4),
+            "\x1B[31mhe\x1B[0ml…"
+        );
+    }
+
+    #[test]
+    fn wrapping() {
+        assert_eq!(wrap("one two three", 20, 2), "one two three");
+        assert_eq!(wrap("one two three", 8, 2), "one two\n  three");
+        assert_eq!(wrap("one two three", 7, 0), "one two\nthree");
+        assert_eq!(wrap("abcdefghij", 4, 0), "abcd\nefgh\nij");
+        assert_eq!(wrap("a\nb", 10, 2), "a\n  b");
+        assert_eq!(wrap("日本語 日本語", 8, 0), "日本語\n日本語");
+    }
+
+    #[test]
+    fn wrapping_keeps_styles() {
+        assert_eq!(
+            wrap("\x1B[31mone two\x1B[0m three", 6, 1),
+            "\x1B[31mone\x1B[0m\n \x1B[31mtwo\x1B[0m\n three"
+        );
+        assert_eq!(
+            wrap("\x1B[1m\x1B[32mab cd", 3, 0),
+            "\x1B[1m\x1B[32mab\x1B[0m\n\x1B[1m\x1B[32mcd"
+        );
+    }
+
+    #[test]
+    fn aligning() {
+        assert_eq!(align("ab", 5, Align::Left), "ab   ");
+        assert_eq!(align("ab", 5, Align::Right), "   ab");
+        assert_eq!(align("ab", 5, Align::Center), " ab  ");
+        assert_eq!(
+            align("\x1B[1mab\x1B[0m", 3, Align::Right),
+            " \x1B[1mab\x1B[0m"
+        );
+        assert_eq!(align("日本", 5, Align::Left), "日本 ");
+        assert_eq!(align("toolong", 3, Align::Left), "toolong");
+    }
+}
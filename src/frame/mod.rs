@@ -0,0 +1,232 @@
+//! Boxes drawn around messages that shouldn't be missed
+//!
+//! # Example
+//! ```
+//! use paris::{Border, Frame};
+//!
+//! let mut frame = Frame::new("Update available <red>1.0.0</> → <green>1.1.0</>");
+//! frame
+//!     .title("<bold>paris</>")
+//!     .padding(2)
+//!     .border(Border::Double)
+//!     .style("<yellow>");
+//!
+//! println!("{}", frame);
+//! ```
+use crate::formatter::{self, width, Align};
+use crate::table::Border;
+use std::fmt::{self, Display, Formatter};
+
+/// A message with a border around it. Widths are worked
+/// out from what ends up on screen, like in tables.
+#[derive(Clone, Debug)]
+pub struct Frame {
+    message: String,
+    title: Option<String>,
+    padding: usize,
+    border: Option<Border>,
+    style: String,
+}
+
+impl Frame {
+    /// A frame around the message, which can have keys and several lines
+    pub fn new<T: Display>(message: T) -> Self {
+        Self {
+            message: message.to_string(),
+            title: None,
+            padding: 1,
+            border: None,
+            style: String::new(),
+        }
+    }
+
+    /// Sets a title, shown in the top border
+    pub fn title<T: Display>(&mut self, title: T) -> &mut Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Empty columns between the border and the message
+    /// on each side, 1 by default
+    pub fn padding(&mut self, padding: usize) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets what the border is drawn with, the
+    /// default one follows the icon set like in tables
+    pub fn border(&mut self, border: Border) -> &mut Self {
+        self.border = Some(border);
+        self
+    }
+
+    /// Keys the border is drawn with, like `"<yellow>"` or `"<dimmed>"`
+    pub fn style<S: Into<String>>(&mut self, style: S) -> &mut Self {
+        self.style = style.into();
+        self
+    }
+
+    /// Every line of the frame, with keys replaced by `colorize`
+    pub(crate) fn lines<F>(&self, mut colorize: F) -> Vec<String>
+    where
+        F: FnMut(&str) -> String,
+    {
+        // Wrapping at no width at all only splits the lines,
+        // but carries styles over from one to the next
+        let message = width::wrap(&colorize(&self.message), usize::MAX, 0);
+        let content: Vec<&str> = message.split('\n').collect();
+        let title = self.title.as_ref().map(|title| colorize(title));
+
+        let mut paint = |part: &str| {
+            if self.style.is_empty() {
+                return part.to_string();
+            }
+
+            colorize(&format!("{}{}</>", self.style, formatter::escape(part)))
+        };
+
+        let padding = " ".repeat(self.padding);
+        let title_width = title.as_deref().map(width::width).unwrap_or(0);
+        let content_width = content.iter().map(|l| width::width(l)).max().unwrap_or(0);
+
+        let border = match self.border.unwrap_or_default().lines() {
+            Some(border) => border,
+            None => {
+                let content_width = content_width.max(title_width);
+                let lines = title.iter().map(String::as_str).chain(content);
+
+                return lines
+                    .map(|line| {
+                        let line = width::align(line, content_width, Align::Left);
+                        format!("{}{}{}", padding, line, padding)
+                            .trim_end()
+                            .to_string()
+                    })
+                    .collect();
+            }
+        };
+
+        // Room for the title with a bit of line on both sides
+        let inner = match title {
+            Some(_) => (content_width + self.padding * 2).max(title_width + 4),
+            None => content_width + self.padding * 2,
+        };
+
+        let [top_left, _, top_right] = border.top;
+        let [bottom_left, _, bottom_right] = border.bottom;
+
+        let top = match title.as_ref() {
+            Some(title) => format!(
+                "{}{} {} {}",
+                paint(top_left),
+                paint(border.horizontal),
+                title,
+                paint(&format!(
+                    "{}{}",
+                    border.horizontal.repeat(inner - title_width - 3),
+                    top_right
+                )),
+            ),
+            None => paint(&format!(
+                "{}{}{}",
+                top_left,
+                border.horizontal.repeat(inner),
+                top_right
+            )),
+        };
+
+        let mut lines = vec![top];
+        let side = paint(border.vertical);
+
+        for line in content.iter() {
+            let line = width::align(line, inner - self.padding * 2, Align::Left);
+            lines.push(format!("{}{}{}{}{}", side, padding, line, padding, side));
+        }
+
+        lines.push(paint(&format!(
+            "{}{}{}",
+            bottom_left,
+            border.horizontal.repeat(inner),
+            bottom_right
+        )));
+
+        lines
+    }
+}
+
+impl Display for Frame {
+    /// Writes the frame with keys replaced, without custom styles.
+    /// Use `Logger::frame` for those.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let lines = self.lines(|text| formatter::colorize_string(text));
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::{pin_icon_set, IconSet};
+
+    fn plain(frame: &Frame) -> String {
+        frame
+            .lines(|text| formatter::format_string(text, false))
+            .join("\n")
+    }
+
+    #[test]
+    fn simple_frame() {
+        let _icons = pin_icon_set(IconSet::Unicode);
+        let frame = Frame::new("hello\n<red>paris</> 日本");
+
+        assert_eq!(
+            plain(&frame),
+            "┌────────────┐\n\
+             │ hello      │\n\
+             │ paris 日本 │\n\
+             └────────────┘"
+        );
+    }
+
+    #[test]
+    fn titles() {
+        let mut frame = Frame::new("hi");
+        frame
+            .title("<bold>Notice</>")
+            .border(Border::Ascii)
+            .padding(2);
+
+        assert_eq!(
+            plain(&frame),
+            "+- Notice -+\n\
+             |  hi      |\n\
+             +----------+"
+        );
+
+        frame.title("a");
+        assert_eq!(plain(&frame).lines().next(), Some("+- a --+"));
+    }
+
+    #[test]
+    fn no_border() {
+        let mut frame = Frame::new("one\ntwo");
+        frame.title("Title").border(Border::None);
+
+        assert_eq!(plain(&frame), " Title\n one\n two");
+    }
+
+    #[test]
+    fn styles() {
+        let mut frame = Frame::new("<green>a\nb</>");
+        frame.style("<red>").padding(0).border(Border::Rounded);
+
+        let rendered = frame.to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(lines[0], "\x1B[31m╭─╮\x1B[0m");
+        assert_eq!(lines[1], "\x1B[31m│\x1B[0m\x1B[32ma\x1B[0m\x1B[31m│\x1B[0m");
+        assert_eq!(lines[2], "\x1B[31m│\x1B[0m\x1B[32mb\x1B[0m\x1B[31m│\x1B[0m");
+        assert_eq!(lines[3], "\x1B[31m╰─╯\x1B[0m");
+    }
+}
@@ -0,0 +1,199 @@
+//! Log levels and the templates that decorate them
+#[cfg(not(feature = "no_logger"))]
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The different kinds of messages that get some flair
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+    /// `info`, cyan ℹ by default
+    Info,
+
+    /// `success`, green ✔ by default
+    Success,
+
+    /// `warn`, yellow ⚠ by default
+    Warn,
+
+    /// `error`, red ✖ by default
+    Error,
+}
+
+impl FromStr for Level {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "info" => Ok(Level::Info),
+            "success" => Ok(Level::Success),
+            "warn" | "warning" => Ok(Level::Warn),
+            "error" => Ok(Level::Error),
+            _ => Err(()),
+        }
+    }
+}
+
+/// What each level looks like. A template is a string with keys
+/// in it and a `{}` where the message goes. Without a `{}` the
+/// template is put in front of the message.
+///
+/// # Example
+/// ```
+/// use paris::{Level, Templates};
+///
+/// let mut templates = Templates::labels();
+/// templates.set(Level::Info, "<blue>info:</> {}");
+///
+/// assert_eq!(templates.get(Level::Info), "<blue>info:</> {}");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Templates {
+    info: String,
+    success: String,
+    warn: String,
+    error: String,
+}
+
+impl Default for Templates {
+    fn default() -> Self {
+        Self::icons()
+    }
+}
+
+impl Templates {
+    /// Colored icons in front of the message, this is the default.
+    /// `ℹ`, `✔`, `⚠` and `✖`.
+    pub fn icons() -> Self {
+        Self::new(
+            "<cyan><info></> {}",
+            "<green><tick></> {}",
+            "<yellow><warn></> {}",
+            "<red><cross></> {}",
+        )
+    }
+
+    /// Colored labels like `[INFO]`, padded so
+    /// all messages start in the same column
+    pub fn labels() -> Self {
+        Self::new(
+            "<cyan>[INFO]</>    {}",
+            "<green>[SUCCESS]</> {}",
+            "<yellow>[WARN]</>    {}",
+            "<red>[ERROR]</>   {}",
+        )
+    }
+
+    /// Nothing but the message
+    pub fn plain() -> Self {
+        Self::new("{}", "{}", "{}", "{}")
+    }
+
+    /// One of the presets by name, `icons`, `labels` or `plain`
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_ref() {
+            "icons" => Some(Self::icons()),
+            "labels" => Some(Self::labels()),
+            "plain" => Some(Self::plain()),
+            _ => None,
+        }
+    }
+
+    /// Replaces the template for the given level
+    pub fn set<S: Into<String>>(&mut self, level: Level, template: S) -> &mut Self {
+        *self.get_mut(level) = template.into();
+        self
+    }
+
+    /// The template for the given level
+    pub fn get(&self, level: Level) -> &str {
+        match level {
+            Level::Info => &self.info,
+            Level::Success => &self.success,
+            Level::Warn => &self.warn,
+            Level::Error => &self.error,
+        }
+    }
+
+    /// Puts the message into the template of the given level
+    #[cfg(not(feature = "no_logger"))]
+    pub(crate) fn render<T: Display>(&self, level: Level, message: T) -> String {
+        let (before, after) = self.split(level);
+
+        format!("{}{}{}", before, message, after)
+    }
+
+    /// What goes before and after the message for the given level
+    pub(crate) fn split(&self, level: Level) -> (&str, &str) {
+        let template = self.get(level);
+
+        match template.find("{}") {
+            Some(at) => (&template[..at], &template[at + 2..]),
+            None => (template, ""),
+        }
+    }
+
+    fn new(info: &str, success: &str, warn: &str, error: &str) -> Self {
+        Self {
+            info: info.to_string(),
+            success: success.to_string(),
+            warn: warn.to_string(),
+            error: error.to_string(),
+        }
+    }
+
+    fn get_mut(&mut self, level: Level) -> &mut String {
+        match level {
+            Level::Info => &mut self.info,
+            Level::Success => &mut self.success,
+            Level::Warn => &mut self.warn,
+            Level::Error => &mut self.error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_levels() {
+        assert_eq!("info".parse(), Ok(Level::Info));
+        assert_eq!("WARNING".parse(), Ok(Level::Warn));
+        assert_eq!("debug".parse::<Level>(), Err(()));
+    }
+
+    #[test]
+    fn presets() {
+        assert_eq!(Templates::default(), Templates::icons());
+        assert_eq!(Templates::preset("Labels"), Some(Templates::labels()));
+        assert_eq!(Templates::preset("plain"), Some(Templates::plain()));
+        assert_eq!(Templates::preset("fancy"), None);
+    }
+
+    #[test]
+    fn split() {
+        let mut templates = Templates::plain();
+        templates
+            .set(Level::Info, "<b>")
+            .set(Level::Warn, "<b>{}</b> {}");
+
+        assert_eq!(templates.split(Level::Info), ("<b>", ""));
+        assert_eq!(templates.split(Level::Warn), ("<b>", "</b> {}"));
+        assert_eq!(templates.split(Level::Error), ("", ""));
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_logger"))]
+    fn render() {
+        let mut templates = Templates::default();
+
+        assert_eq!(templates.render(Level::Info, "hi"), "<cyan><info></> hi");
+
+        templates
+            .set(Level::Warn, "[WARN] ")
+            .set(Level::Error, "{} <red>(error)</>");
+
+        assert_eq!(templates.render(Level::Warn, "{}"), "[WARN] {}");
+        assert_eq!(templates.render(Level::Error, "{}"), "{} <red>(error)</>");
+    }
+}
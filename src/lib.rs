@@ -49,6 +49,22 @@
 //!        .success("and this is 5 lines under all other messages");
 //!     # }
 //!
+//! # Broken pipes
+//! Piping your output into something like `head` closes the pipe
+//! early. Instead of panicking like `print!` does, all methods quietly
+//! ignore that. If you'd rather know about it, every method that writes
+//! has a `try_` equivalent that returns an `io::Result`.
+//!
+//!     # #[cfg(not(feature = "no_logger"))] {
+//!     # use paris::Logger;
+//!     # fn main() -> std::io::Result<()> {
+//!     # let mut log = Logger::new();
+//!     log.try_info("this might fail")?
+//!        .try_success("but it didn't");
+//!     # Ok(())
+//!     # }
+//!     # }
+//!
 //! # Customisation
 //! Outputting text is cool. Outputting text with a colored icon
 //! at the start is even cooler! But this crate is all about
@@ -85,6 +101,14 @@
 //!     log.info("<blue><on-bright-red> This text is blue on a bright red background</> it's a pain");
 //!     # }
 //!
+//! Or put a few of them in the same tag, separated by spaces or `+`.
+//!
+//!     # #[cfg(not(feature = "no_logger"))] {
+//!     # use paris::Logger;
+//!     # let mut log = Logger::new();
+//!     log.info("<bold red on-black>All at once</> or <red+bold>like this</>");
+//!     # }
+//!
 //! If you feel like writing a lot of colors by hand is too tedious, or if you know you're going
 //! to be using the same combination of colors over and over again you can create a `custom style`
 //! that encapsulates all those colors.
@@ -98,21 +122,56 @@
 //!     log.info("<lol>This is has all your new styles</>");
 //!     # }
 //!
+//! Styles can build on other styles too, and adding a style that already exists replaces it.
+//!
+//!     # #[cfg(not(feature = "no_logger"))] {
+//!     # use paris::Logger;
+//!     # let mut log = Logger::new();
+//!     log.add_style("lmao", vec!["lol", "underline"]);
+//!     log.remove_style("lol"); // '<lmao>' is now just underlined
+//!     # }
+//!
 //! See [the README](https://github.com/SirTheViking/logger/blob/master/README.md) for a full list of keys
 //! if you're not feeling confident in your ability to name colors. It happens.
 //!
 //! ### Resetting
-//! You've probably seen the `</>` tag in the above logs. It's not there to
-//! _"close the previously opened tag"_ no no. You can open as many tags as you want
-//! and only use `</>` once, it's just the _"reset everything to default"_ tag, You might
-//! decide you don't ever want to use it. It's up to you.
-
-//! However, resetting everything to default might not be what you want. Most of the time
-//! it'll be enough, but for those times when it isn't there are a few other tags such as:
-
+//! You've probably seen the `</>` tag in the above logs. It's the
+//! _"reset everything to default"_ tag. You can open as many tags as you want
+//! and only use `</>` once, or you might decide you don't ever want to use it.
+//! Whatever is still open when the message ends gets reset automatically,
+//! so nothing bleeds into the next line. The same goes for raw escape sequences
+//! you put in yourself, a reset is only added if something is actually left on.
+//!
+//! If you'd rather close a specific tag, write its name after the slash. Closing
+//! a tag brings back exactly what was there before it was opened.
+//!
+//!     # #[cfg(not(feature = "no_logger"))] {
+//!     # use paris::Logger;
+//!     # let mut log = Logger::new();
+//!     // 'c' is red again, not default
+//!     log.info("<red>a <bold>b</bold> c</red>");
+//!     # }
+//!
+//! There are also a few tags that reset only part of the style:
+//!
 //! * `<///>` only resets the background
 //! * `<//>` only reset the foreground
 //!
+//! ### Escaping
+//! If you need to write something that looks like a key, like `Vec<T>` or
+//! some HTML, double its opening and it'll be written out as is. `<<red>` is
+//! written as `<red>`, while a `<<` that isn't followed by a key, like
+//! `a << b`, is left alone. For text you don't control, `formatter::escape`
+//! does that for you.
+//!
+//!     # #[cfg(not(feature = "no_logger"))] {
+//!     # use paris::Logger;
+//!     # use paris::formatter::escape;
+//!     # let mut log = Logger::new();
+//!     log.info("<<b> isn't bold");
+//!     log.info(format!("<red>{}</>", escape("<b>from somewhere else</b>")));
+//!     # }
+//!
 //! ### Macros
 //! With the macros feature enabled, you get access to macro equivalents
 //! of the logger functions.
@@ -131,6 +190,32 @@
 //! Every macro has the same functionality as its `Logger`
 //! equivalent. Colors and icon keys work just the same.
 //!
+//! The format string given to a macro is parsed while compiling, so
+//! colors and styles written in it cost nothing at runtime, only the
+//! arguments and icons are dealt with then. Unknown tags in the format
+//! string that look like a typo of a known one, like `<bule>`, show up as a
+//! (deprecation) warning so they don't go unnoticed. Things like `<T>` or
+//! `<u8>` are left alone, and `#[allow(deprecated)]` silences the rest.
+//!
+//! Format strings that aren't literals, like `concat!(...)`, still work
+//! but are dealt with at runtime, and can't take their width or
+//! precision from an argument (`{:.*}`, `{:1$}`).
+//!
+//! Keys inside the arguments are replaced as well, which is handy until
+//! you log something you don't control. Switch the macros to
+//! `output::Interpolation::Verbatim` and only the format string is looked at,
+//! whether it's a literal or not. Raw escape sequences in the arguments
+//! still get through unless `output::set_sanitize` is told otherwise.
+//!
+//!     # #[cfg(feature = "macros")] {
+//!     # use paris::info;
+//!     use paris::output::{set_interpolation, Interpolation};
+//!
+//!     set_interpolation(Interpolation::Verbatim);
+//!     info!("<green>{}</>", "<red>stays exactly like this</>");
+//!     # set_interpolation(Interpolation::Tagged);
+//!     # }
+//!
 //! See [the Logger struct](https://docs.rs/paris/) for all methods and their macro equivalents
 #![warn(missing_docs)]
 
@@ -139,13 +224,32 @@ mod timestamp;
 
 #[cfg(feature = "macros")]
 mod macros;
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub use paris_macros::pieces as __pieces;
+
+#[cfg(any(feature = "macros", not(feature = "no_logger")))]
+mod level;
+#[cfg(any(feature = "macros", not(feature = "no_logger")))]
+pub use level::{Level, Templates};
 
 #[cfg(not(feature = "no_logger"))]
 mod logger;
 #[cfg(not(feature = "no_logger"))]
 pub use logger::Logger;
 
+#[cfg(not(feature = "no_logger"))]
+mod theme;
+#[cfg(not(feature = "no_logger"))]
+pub use theme::{Theme, ThemeError};
+
 pub mod formatter;
+mod frame;
 pub mod output;
+mod table;
+mod tree;
 
-pub use formatter::LogIcon;
+pub use formatter::{Align, LogIcon};
+pub use frame::Frame;
+pub use table::{Border, Table};
+pub use tree::Tree;
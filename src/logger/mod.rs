@@ -1,12 +1,16 @@
+use std::collections::HashSet;
 use std::fmt::Display;
-use std::io;
-use std::io::prelude::*;
+use std::io::{self, prelude::*, BufWriter};
 use std::sync::{Arc, RwLock};
 use std::thread;
 use std::time::Duration;
 
-use crate::formatter::{colorize_string, Ansi, Formatter};
+use crate::formatter::{
+    self, colorize_string, escape, width, Align, Ansi, DiagnosticKind, Formatter, IconSet,
+    Sanitize, Wrap,
+};
 use crate::output;
+use crate::{Frame, Level, Table, Templates, Theme, Tree};
 
 #[allow(missing_docs)]
 pub struct Logger<'a> {
@@ -15,6 +19,23 @@ pub struct Logger<'a> {
 
     line_ending: String,
     formatter: Formatter<'a>,
+    templates: Templates,
+
+    buffer: Option<BufWriter<io::Stdout>>,
+
+    strict: bool,
+    warned: HashSet<String>,
+    wrap: Wrap,
+
+    groups: usize,
+    group_width: usize,
+    group_guides: bool,
+
+    rule_line: Option<String>,
+    rule_style: String,
+
+    kv_keys: String,
+    kv_values: String,
 }
 
 impl<'a> Default for Logger<'a> {
@@ -25,10 +46,34 @@ impl<'a> Default for Logger<'a> {
 
             line_ending: String::from("\n"),
             formatter: Formatter::new(),
+            templates: Templates::default(),
+
+            buffer: None,
+
+            strict: false,
+            warned: HashSet::new(),
+            wrap: Wrap::Off,
+
+            groups: 0,
+            group_width: 2,
+            group_guides: false,
+
+            rule_line: None,
+            rule_style: String::new(),
+
+            kv_keys: String::from("<bold>"),
+            kv_values: String::new(),
         }
     }
 }
 
+impl<'a> Drop for Logger<'a> {
+    fn drop(&mut self) {
+        // Nowhere to report errors from here
+        let _ = self.try_flush();
+    }
+}
+
 impl<'a> Logger<'a> {
     /// Initializes a new logger
     ///
@@ -41,6 +86,32 @@ impl<'a> Logger<'a> {
         Self::default()
     }
 
+    /// Initializes a new logger that buffers everything going
+    /// to stdout instead of writing it out line by line. Useful
+    /// when logging thousands of lines.
+    ///
+    /// The buffer is flushed when calling [`flush`](#method.flush),
+    /// before writing an error, before starting a loading animation
+    /// and when the logger is dropped.
+    ///
+    /// # Example
+    /// ```
+    /// use paris::Logger;
+    /// let mut logger = Logger::buffered();
+    ///
+    /// for i in 0..1000 {
+    ///     logger.info(i);
+    /// }
+    ///
+    /// logger.flush();
+    /// ```
+    pub fn buffered() -> Self {
+        let mut logger = Self::default();
+        logger.buffer = Some(BufWriter::new(io::stdout()));
+
+        logger
+    }
+
     /// Prints to stdout with no bells and whistles. I does however
     /// add a timestamp if enabled.
     ///
@@ -54,7 +125,14 @@ impl<'a> Logger<'a> {
     ///
     /// Equivalent macro: `log!()`
     pub fn log<T: Display>(&mut self, message: T) -> &mut Self {
-        self.stdout(message)
+        let result = self.try_log(message).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`log`](#method.log) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_log<T: Display>(&mut self, message: T) -> io::Result<&mut Self> {
+        self.try_stdout(None, message)
     }
 
     /// Prints to stdout and adds some info flair to the text
@@ -68,7 +146,14 @@ impl<'a> Logger<'a> {
     ///
     /// Equivalent macro: `info!()`
     pub fn info<T: Display>(&mut self, message: T) -> &mut Self {
-        self.stdout(format!("<cyan><info></> {}", message))
+        let result = self.try_info(message).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`info`](#method.info) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_info<T: Display>(&mut self, message: T) -> io::Result<&mut Self> {
+        self.try_stdout(Some(Level::Info), message)
     }
 
     /// Prints to stdout and adds some success flair to text
@@ -82,7 +167,14 @@ impl<'a> Logger<'a> {
     ///
     /// Equivalent macro: `success!()`
     pub fn success<T: Display>(&mut self, message: T) -> &mut Self {
-        self.stdout(format!("<green><tick></> {}", message))
+        let result = self.try_success(message).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`success`](#method.success) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_success<T: Display>(&mut self, message: T) -> io::Result<&mut Self> {
+        self.try_stdout(Some(Level::Success), message)
     }
 
     /// Prints to stdout and adds some warning flare to text
@@ -96,7 +188,14 @@ impl<'a> Logger<'a> {
     ///
     /// Equivalent macro: `warn!()`
     pub fn warn<T: Display>(&mut self, message: T) -> &mut Self {
-        self.stdout(format!("<yellow><warn></> {}", message))
+        let result = self.try_warn(message).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`warn`](#method.warn) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_warn<T: Display>(&mut self, message: T) -> io::Result<&mut Self> {
+        self.try_stdout(Some(Level::Warn), message)
     }
 
     /// Prints to stderr and adds some error flare to text
@@ -110,7 +209,14 @@ impl<'a> Logger<'a> {
     ///
     /// Equivalent macro: `error!()`
     pub fn error<T: Display>(&mut self, message: T) -> &mut Self {
-        self.stderr(format!("<red><cross></> {}", message))
+        let result = self.try_error(message).map(drop);
+        self.ignore_broken_pipe(result, "stderr")
+    }
+
+    /// Same as [`error`](#method.error) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_error<T: Display>(&mut self, message: T) -> io::Result<&mut Self> {
+        self.try_stderr(Some(Level::Error), message)
     }
 
     /// Prints a specified amount of newlines to stdout
@@ -126,9 +232,16 @@ impl<'a> Logger<'a> {
     ///     .info("And some more in between");
     /// ```
     pub fn newline(&mut self, amount: usize) -> &mut Self {
-        self.done();
-        print!("{}", "\n".repeat(amount));
-        self
+        let result = self.try_newline(amount).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`newline`](#method.newline) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_newline(&mut self, amount: usize) -> io::Result<&mut Self> {
+        self.try_done()?;
+        self.try_write_stdout(|out| out.write_all("\n".repeat(amount).as_bytes()))?;
+        Ok(self)
     }
 
     /// Prints a specified amount of tabs to stdout
@@ -143,11 +256,278 @@ impl<'a> Logger<'a> {
     ///     .newline(5);
     /// ```
     pub fn indent(&mut self, amount: usize) -> &mut Self {
-        self.done();
-        print!("{}", "\t".repeat(amount));
+        let result = self.try_indent(amount).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`indent`](#method.indent) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_indent(&mut self, amount: usize) -> io::Result<&mut Self> {
+        self.try_done()?;
+        self.try_write_stdout(|out| out.write_all("\t".repeat(amount).as_bytes()))?;
+        Ok(self)
+    }
+
+    /// Prints the title and indents everything after it until
+    /// [`group_end`](#method.group_end) is called. Groups can go
+    /// inside other groups.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// let mut logger = Logger::new();
+    /// logger
+    ///     .group("<bold>Building</>")
+    ///     .info("Compiling paris")
+    ///     .group("Running tests")
+    ///     .success("All passed")
+    ///     .group_end()
+    ///     .group_end()
+    ///     .success("Done");
+    /// ```
+    pub fn group<T: Display>(&mut self, title: T) -> &mut Self {
+        let result = self.try_group(title).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`group`](#method.group) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_group<T: Display>(&mut self, title: T) -> io::Result<&mut Self> {
+        self.try_stdout(None, title)?;
+        self.groups += 1;
+        Ok(self)
+    }
+
+    /// Ends the innermost group, messages go back
+    /// to where they were before it started
+    pub fn group_end(&mut self) -> &mut Self {
+        self.groups = self.groups.saturating_sub(1);
+        self
+    }
+
+    /// How many columns each group indents by, 2 by default
+    pub fn group_indent(&mut self, width: usize) -> &mut Self {
+        self.group_width = width;
+        self
+    }
+
+    /// Draw a line down the side of each group
+    /// so it's easier to see where it ends
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// let mut logger = Logger::new();
+    /// logger
+    ///     .group_guides(true)
+    ///     .group("Building")
+    ///     .info("Shows up as │ ℹ Compiling")
+    ///     .group_end();
+    /// ```
+    pub fn group_guides(&mut self, enabled: bool) -> &mut Self {
+        self.group_guides = enabled;
+        self
+    }
+
+    /// Prints a table to stdout. Custom styles work in the cells.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::{Logger, Table};
+    /// let mut logger = Logger::new();
+    /// let mut table = Table::new();
+    /// table
+    ///     .headers(vec!["Name", "Status"])
+    ///     .row(vec!["build", "<green>ok</>"])
+    ///     .row(vec!["tests", "<red>failed</>"]);
+    ///
+    /// logger.table(&table);
+    /// ```
+    pub fn table(&mut self, table: &Table) -> &mut Self {
+        let result = self.try_table(table).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`table`](#method.table) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_table(&mut self, table: &Table) -> io::Result<&mut Self> {
+        let lines = self.colorize_each(|colorize| table.lines(colorize))?;
+        self.try_block(lines)
+    }
+
+    /// Prints a tree to stdout. Custom styles work in the labels.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::{Logger, Tree};
+    /// let mut logger = Logger::new();
+    /// let mut tree = Tree::new("<bold>my-app</>");
+    /// tree.leaf("paris").leaf("serde");
+    ///
+    /// logger.tree(&tree);
+    /// ```
+    pub fn tree(&mut self, tree: &Tree) -> &mut Self {
+        let result = self.try_tree(tree).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`tree`](#method.tree) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_tree(&mut self, tree: &Tree) -> io::Result<&mut Self> {
+        let lines = self.colorize_each(|colorize| tree.lines(colorize))?;
+        self.try_block(lines)
+    }
+
+    /// Prints pairs of keys and values, the keys lined up in a column.
+    /// Values with several lines, or that get wrapped, stay to the
+    /// right of the keys.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// let mut logger = Logger::new();
+    /// logger.kv(&[
+    ///     ("Version", "1.5.15"),
+    ///     ("Status", "<green>up to date</>"),
+    ///     ("Features", "timestamps\nmacros"),
+    /// ]);
+    /// ```
+    pub fn kv<K: Display, V: Display>(&mut self, pairs: &[(K, V)]) -> &mut Self {
+        let result = self.try_kv(pairs).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`kv`](#method.kv) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_kv<K: Display, V: Display>(&mut self, pairs: &[(K, V)]) -> io::Result<&mut Self> {
+        let lines = self.render_kv(pairs)?;
+        self.try_block(lines)
+    }
+
+    /// Keys that keys and values in [`kv`](#method.kv) are written with.
+    /// Keys are `"<bold>"` and values have nothing by default.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// let mut logger = Logger::new();
+    /// logger
+    ///     .kv_style("<cyan>", "<dimmed>")
+    ///     .kv(&[("Status", "fine")]);
+    /// ```
+    pub fn kv_style(&mut self, keys: &str, values: &str) -> &mut Self {
+        self.kv_keys = keys.to_string();
+        self.kv_values = values.to_string();
+        self
+    }
+
+    /// Prints a line across the terminal, with a title
+    /// in the middle if there is one. Handy between steps.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// let mut logger = Logger::new();
+    /// logger
+    ///     .rule(Some("<bold>Testing</>"))
+    ///     .info("Running 52 tests")
+    ///     .rule(None);
+    /// ```
+    pub fn rule(&mut self, title: Option<&str>) -> &mut Self {
+        let result = self.try_rule(title).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`rule`](#method.rule) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_rule(&mut self, title: Option<&str>) -> io::Result<&mut Self> {
+        let rule = self.render_rule(title)?;
+        self.try_block(vec![rule])
+    }
+
+    /// Prints the text centered between two lines
+    /// across the terminal, to start a new section
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// let mut logger = Logger::new();
+    /// logger.header("<bold>Deploying</>");
+    /// ```
+    pub fn header<T: Display>(&mut self, text: T) -> &mut Self {
+        let result = self.try_header(text).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`header`](#method.header) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_header<T: Display>(&mut self, text: T) -> io::Result<&mut Self> {
+        let rule = self.render_rule(None)?;
+        let columns = self.columns();
+        let text = self.colorize(&text.to_string())?;
+        let text = width::align(&width::truncate(&text, columns), columns, Align::Center);
+
+        self.try_block(vec![rule.clone(), text.trim_end().to_string(), rule])
+    }
+
+    /// What rules and headers are drawn with, `─` by default.
+    /// The line is made of as many of these as fit.
+    pub fn rule_line(&mut self, line: &str) -> &mut Self {
+        self.rule_line = Some(line.to_string());
         self
     }
 
+    /// Keys rules and headers are drawn with, like `"<dimmed>"`.
+    /// Titles have their own keys.
+    pub fn rule_style(&mut self, style: &str) -> &mut Self {
+        self.rule_style = style.to_string();
+        self
+    }
+
+    /// Prints the message in a box, for things that shouldn't be missed.
+    /// Use [`frame`](#method.frame) for a title or a different border.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// let mut logger = Logger::new();
+    /// logger.boxed("Update available\n<red>1.0.0</> → <green>1.1.0</>");
+    /// ```
+    pub fn boxed<T: Display>(&mut self, message: T) -> &mut Self {
+        let result = self.try_boxed(message).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`boxed`](#method.boxed) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_boxed<T: Display>(&mut self, message: T) -> io::Result<&mut Self> {
+        self.try_frame(&Frame::new(message))
+    }
+
+    /// Prints a frame to stdout. Custom styles work in
+    /// the message, the title and the border style.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::{Border, Frame, Logger};
+    /// let mut logger = Logger::new();
+    /// let mut frame = Frame::new("Breaking change ahead");
+    /// frame.title("<bold>Heads up</>").border(Border::Heavy).style("<yellow>");
+    ///
+    /// logger.frame(&frame);
+    /// ```
+    pub fn frame(&mut self, frame: &Frame) -> &mut Self {
+        let result = self.try_frame(frame).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`frame`](#method.frame) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_frame(&mut self, frame: &Frame) -> io::Result<&mut Self> {
+        let lines = self.colorize_each(|colorize| frame.lines(colorize))?;
+        self.try_block(lines)
+    }
+
     /// Starts a loading animation with the given message.
     ///
     /// # Example
@@ -177,9 +557,21 @@ impl<'a> Logger<'a> {
     /// logger.error("I give up, I can't do it again!");
     /// ```
     pub fn loading<T: Display>(&mut self, message: T) -> &mut Self {
+        let result = self.try_loading(message).map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`loading`](#method.loading) but returns any error that happened
+    /// while clearing the previous animation or flushing the buffer. Errors
+    /// from the animation itself only stop it.
+    pub fn try_loading<T: Display>(&mut self, message: T) -> io::Result<&mut Self> {
         // If already running, stop the currently running thread
         // and clean it up before adding a new message.
-        self.done();
+        self.try_done()?;
+
+        // The animation writes straight to stdout, anything
+        // still in the buffer has to go out before it
+        self.try_flush()?;
 
         let mut status = self.is_loading.write().unwrap();
         *status = true;
@@ -187,7 +579,7 @@ impl<'a> Logger<'a> {
         drop(status); // Release the lock so a mutable can be returned
 
         let status = self.is_loading.clone();
-        let message = self.formatter.colorize(&message.to_string());
+        let message = self.colorize(&message.to_string())?;
 
         self.loading_handle = Some(thread::spawn(move || {
             let frames: [&str; 6] = ["⠦", "⠇", "⠋", "⠙", "⠸", "⠴"];
@@ -198,9 +590,13 @@ impl<'a> Logger<'a> {
                     i = 0;
                 }
 
-                let message = format!("<cyan>{}</> {}", frames[i], &message);
-                output::stdout(colorize_string(message), "", true);
-                io::stdout().flush().unwrap();
+                let written = output::try_stdout(loading_frame(frames[i], &message), "", true)
+                    .and_then(|_| io::stdout().flush());
+
+                // Nobody is listening anymore, no point in animating
+                if written.is_err() {
+                    break;
+                }
 
                 thread::sleep(Duration::from_millis(100));
 
@@ -208,7 +604,7 @@ impl<'a> Logger<'a> {
             }
         }));
 
-        self
+        Ok(self)
     }
 
     /// Stops the loading animation and clears the line so you can print something else
@@ -216,8 +612,15 @@ impl<'a> Logger<'a> {
     /// call this one automatically when called so you can use one of those directly
     /// for less clutter.
     pub fn done(&mut self) -> &mut Self {
+        let result = self.try_done().map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`done`](#method.done) but returns any error
+    /// that happened while clearing the line instead of swallowing it.
+    pub fn try_done(&mut self) -> io::Result<&mut Self> {
         if !*self.is_loading.read().unwrap() {
-            return self;
+            return Ok(self);
         }
 
         let mut status = self.is_loading.write().unwrap();
@@ -231,8 +634,33 @@ impl<'a> Logger<'a> {
             .join()
             .expect("Could not join spawned thread");
 
-        Ansi::clear_line();
-        self
+        Ansi::try_clear_line()?;
+        Ok(self)
+    }
+
+    /// Writes out everything that's been buffered so far.
+    /// Does nothing if the logger isn't buffered.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// let mut logger = Logger::buffered();
+    ///
+    /// logger.info("Sitting in a buffer").flush();
+    /// ```
+    pub fn flush(&mut self) -> &mut Self {
+        let result = self.try_flush().map(drop);
+        self.ignore_broken_pipe(result, "stdout")
+    }
+
+    /// Same as [`flush`](#method.flush) but returns any error
+    /// that happened while writing instead of swallowing it.
+    pub fn try_flush(&mut self) -> io::Result<&mut Self> {
+        if let Some(buffer) = self.buffer.as_mut() {
+            buffer.flush()?;
+        }
+
+        Ok(self)
     }
 
     /// Forces the next statement to not output a newline
@@ -270,35 +698,445 @@ impl<'a> Logger<'a> {
         self
     }
 
+    /// Change what a level looks like. The template can have any keys
+    /// in it and a `{}` where the message goes.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// use paris::Level;
+    ///
+    /// let mut logger = Logger::new();
+    /// logger.template(Level::Info, "<blue>[{}]</>");
+    ///
+    /// logger.info("Blue and in brackets");
+    /// ```
+    pub fn template<S: Into<String>>(&mut self, level: Level, template: S) -> &mut Self {
+        self.templates.set(level, template);
+        self
+    }
+
+    /// Change what all levels look like at once, usually to one of
+    /// the presets. See [`Templates`](struct.Templates.html).
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// use paris::Templates;
+    ///
+    /// let mut logger = Logger::new();
+    /// logger.templates(Templates::labels());
+    ///
+    /// logger.warn("Starts with [WARN] instead of an icon");
+    /// ```
+    pub fn templates(&mut self, templates: Templates) -> &mut Self {
+        self.templates = templates;
+        self
+    }
+
+    /// Use the styles and level templates from the given theme.
+    /// Styles that already exist are replaced, levels the theme
+    /// doesn't mention keep looking the way they did.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// use paris::Theme;
+    ///
+    /// let theme: Theme = "
+    ///     lol = green bold
+    ///
+    ///     [levels]
+    ///     info = <lol>[INFO]</> {}
+    /// ".parse().unwrap();
+    ///
+    /// let mut logger = Logger::new();
+    /// logger.theme(&theme);
+    ///
+    /// logger.info("Starts with a green and bold [INFO]");
+    /// ```
+    pub fn theme(&mut self, theme: &Theme) -> &mut Self {
+        self.formatter.theme(theme);
+
+        for level in [Level::Info, Level::Success, Level::Warn, Level::Error].iter() {
+            if let Some(template) = theme.template(*level) {
+                self.templates.set(*level, template);
+            }
+        }
+
+        self
+    }
+
+    /// Add an icon that can be used as a key, `<rocket>` for example.
+    /// It only works in this logger, use [`formatter::add_icon`](formatter/fn.add_icon.html)
+    /// for one that works everywhere, the macros included.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// # let mut logger = Logger::new();
+    ///
+    /// logger.add_icon("rocket", "🚀");
+    /// logger.info("<rocket> Launched");
+    /// ```
+    pub fn add_icon(&mut self, name: &str, icon: &str) -> &mut Self {
+        self.formatter.add_icon(name, icon);
+        self
+    }
+
+    /// Turn emoji shortcodes like `<:rocket:>` on or off, they're
+    /// written as plain text when off. See [`Formatter::emoji`](formatter/struct.Formatter.html#method.emoji).
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// # let mut logger = Logger::new();
+    ///
+    /// logger.emoji(false);
+    /// logger.success("<:tada:> Released"); // :tada: Released
+    /// ```
+    pub fn emoji(&mut self, enabled: bool) -> &mut Self {
+        self.formatter.emoji(enabled);
+        self
+    }
+
+    /// Remove a custom key so it's no longer replaced
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// # let mut logger = Logger::new();
+    ///
+    /// logger.add_style("lol", vec!["green"]);
+    /// logger.remove_style("lol");
+    ///
+    /// logger.info("<lol>is just text again");
+    /// ```
+    pub fn remove_style(&mut self, key: &str) -> &mut Self {
+        self.formatter.remove_style(key);
+        self
+    }
+
+    /// Decide what happens to control characters and raw escape
+    /// sequences inside messages, so whatever you log can't move
+    /// the cursor or clear the screen. Colors from keys are kept.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// use paris::formatter::Sanitize;
+    ///
+    /// let mut logger = Logger::new();
+    /// logger.sanitize(Sanitize::Escape);
+    ///
+    /// logger.info("<red>Shows up as \x1b[2J instead of clearing the screen</>");
+    /// ```
+    pub fn sanitize(&mut self, mode: Sanitize) -> &mut Self {
+        self.formatter.sanitize(mode);
+        self
+    }
+
+    /// Break long messages up into several lines, between words where
+    /// possible. Lines after the first start under the message, not
+    /// under the icon, and keep whatever style was on.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// use paris::formatter::Wrap;
+    ///
+    /// let mut logger = Logger::new();
+    /// logger.wrap(Wrap::Terminal);
+    ///
+    /// logger.info("<blue>A very long message that goes on and on</>");
+    /// ```
+    pub fn wrap(&mut self, wrap: Wrap) -> &mut Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Warn on stderr about unknown tags in messages, once per tag.
+    /// Handy in tests or while debugging to catch typos in keys
+    /// that would otherwise just show up as text.
+    ///
+    /// # Example
+    /// ```
+    /// # use paris::Logger;
+    /// let mut logger = Logger::new();
+    /// logger.strict(true);
+    ///
+    /// logger.info("<bule>Typo</>"); // Warns about <bule>
+    /// logger.info("<bule>Again</>"); // Doesn't warn again
+    /// ```
+    pub fn strict(&mut self, enabled: bool) -> &mut Self {
+        self.strict = enabled;
+        self
+    }
+
+    /// Colorizes the message, warning about
+    /// unknown tags first if in strict mode
+    fn colorize(&mut self, message: &str) -> io::Result<String> {
+        if self.strict {
+            let tags = self.unknown_tags(message);
+
+            // Whatever came before goes out first so the
+            // warning ends up right before its message
+            if !tags.is_empty() {
+                self.try_flush()?;
+            }
+
+            for tag in tags {
+                let warning = format!("<yellow><warn></> Unknown tag {}", escape(&tag));
+                output::try_stderr(colorize_string(warning), "\n", false)?;
+            }
+        }
+
+        Ok(self.formatter.colorize(message))
+    }
+
+    /// Colorizes every piece of a table, tree or frame while
+    /// it's laid out, stopping at the first error
+    fn colorize_each<F>(&mut self, lines: F) -> io::Result<Vec<String>>
+    where
+        F: FnOnce(&mut dyn FnMut(&str) -> String) -> Vec<String>,
+    {
+        let mut error = None;
+        let lines = lines(&mut |text| match error {
+            Some(_) => String::new(),
+            None => self.colorize(text).unwrap_or_else(|e| {
+                error = Some(e);
+                String::new()
+            }),
+        });
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(lines),
+        }
+    }
+
+    /// Unknown tags in the message that haven't been warned about yet
+    fn unknown_tags(&mut self, message: &str) -> Vec<String> {
+        let mut tags = vec![];
+
+        for diagnostic in self.formatter.validate(message) {
+            if diagnostic.kind == DiagnosticKind::UnknownTag
+                && self.warned.insert(diagnostic.key.clone())
+            {
+                tags.push(diagnostic.key);
+            }
+        }
+
+        tags
+    }
+
     /// Output to stdout, add timestamps or on the same line
-    fn stdout<T>(&mut self, message: T) -> &mut Self
+    fn try_stdout<T>(&mut self, level: Option<Level>, message: T) -> io::Result<&mut Self>
     where
         T: Display,
     {
-        self.done();
-        let message = message.to_string();
+        self.try_done()?;
+        let message = self.render(level, message)?;
+        let line_ending = self.get_line_ending();
 
-        output::stdout(
-            self.formatter.colorize(&message),
-            &self.get_line_ending(),
-            false,
-        );
-        self
+        self.try_write_stdout(|out| output::try_write(out, message, &line_ending, false))?;
+        Ok(self)
     }
 
     /// Output to stderr, add timestamps or write on the same line
-    fn stderr<T>(&mut self, message: T) -> &mut Self
+    fn try_stderr<T>(&mut self, level: Option<Level>, message: T) -> io::Result<&mut Self>
     where
         T: Display,
     {
-        self.done();
-        let message = message.to_string();
+        self.try_done()?;
+        self.try_flush()?; // Keep stdout and stderr in order
+        let message = self.render(level, message)?;
 
-        output::stderr(
-            self.formatter.colorize(&message),
-            &self.get_line_ending(),
-            false,
-        );
+        output::try_stderr(message, &self.get_line_ending(), false)?;
+        Ok(self)
+    }
+
+    /// Puts the message in the level's template, colorizes it
+    /// and wraps it if wrapping is on. Wrapped lines line up
+    /// with the message instead of the template.
+    fn render<T: Display>(&mut self, level: Option<Level>, message: T) -> io::Result<String> {
+        let message = match level {
+            Some(level) => self.templates.render(level, message),
+            None => message.to_string(),
+        };
+
+        let message = self.colorize(&message)?;
+        let prefix = self.group_prefix();
+
+        let message = match self.wrap.columns() {
+            Some(columns) => {
+                let indent = match level {
+                    Some(level) => {
+                        let (before, _) = self.templates.split(level);
+                        width::width(&self.formatter.colorize(before))
+                    }
+                    None => 0,
+                };
+
+                let columns = columns.saturating_sub(width::width(&prefix));
+                width::wrap(&message, columns, indent)
+            }
+            None => message,
+        };
+
+        Ok(Self::indented(&prefix, &message))
+    }
+
+    /// Columns rules and headers take up. The wrapping width if there
+    /// is one, the terminal's otherwise, minus the group indentation.
+    fn columns(&self) -> usize {
+        let columns = self
+            .wrap
+            .columns()
+            .or_else(output::terminal_width)
+            .unwrap_or(80);
+
+        columns.saturating_sub(width::width(&self.group_prefix()))
+    }
+
+    /// Lines of lined up keys and values
+    fn render_kv<K: Display, V: Display>(&mut self, pairs: &[(K, V)]) -> io::Result<Vec<String>> {
+        let styled = |style: &str, text: String| match style {
+            "" => text,
+            style => format!("{}{}</>", style, text),
+        };
+
+        let pairs = pairs
+            .iter()
+            .map(|(key, value)| {
+                let key = styled(&self.kv_keys, key.to_string());
+                let value = styled(&self.kv_values, value.to_string());
+
+                Ok((format!("{}:", self.colorize(&key)?), self.colorize(&value)?))
+            })
+            .collect::<io::Result<Vec<(String, String)>>>()?;
+
+        let keys = pairs
+            .iter()
+            .map(|(key, _)| width::width(key))
+            .max()
+            .unwrap_or(0);
+
+        let columns = self
+            .wrap
+            .columns()
+            .map(|columns| columns.saturating_sub(width::width(&self.group_prefix())))
+            .unwrap_or(usize::MAX);
+
+        Ok(pairs
+            .iter()
+            .map(|(key, value)| {
+                let line = format!("{} {}", width::align(key, keys, Align::Left), value);
+                width::wrap(&line, columns, keys + 1)
+            })
+            .collect())
+    }
+
+    /// A full line with the title centered in it
+    fn render_rule(&mut self, title: Option<&str>) -> io::Result<String> {
+        let columns = self.columns();
+        let title = title.map(|title| self.colorize(title)).transpose()?;
+
+        // Titles that don't fit are cut short, leaving room for the spaces around them
+        let title = title.map(|title| width::truncate(&title, columns.saturating_sub(2)));
+
+        let (left, right) = match title.as_ref() {
+            Some(title) => {
+                let room = columns.saturating_sub(width::width(title) + 2);
+                (room / 2, room - room / 2)
+            }
+            None => (columns, 0),
+        };
+
+        let line = |columns: usize| {
+            let piece = match self.rule_line.as_ref() {
+                Some(line) => line.as_str(),
+                None => match formatter::icon_set() {
+                    IconSet::Unicode => "─",
+                    IconSet::Ascii => "-",
+                },
+            };
+
+            let line = piece.repeat(columns / width::width(piece).max(1));
+
+            if line.is_empty() || self.rule_style.is_empty() {
+                return line;
+            }
+
+            let line = format!("{}{}</>", self.rule_style, escape(&line));
+            self.formatter.colorize(&line)
+        };
+
+        Ok(match title {
+            Some(title) => format!("{} {} {}", line(left), title, line(right))
+                .trim_end()
+                .to_string(),
+            None => line(left),
+        })
+    }
+
+    /// What goes in front of every line inside the current groups
+    fn group_prefix(&self) -> String {
+        let step = match (self.group_guides, self.group_width) {
+            (_, 0) => return String::new(),
+            (true, width) => {
+                let guide = match formatter::icon_set() {
+                    IconSet::Unicode => "│",
+                    IconSet::Ascii => "|",
+                };
+
+                format!("{}{}", guide, " ".repeat(width - 1))
+            }
+            (false, width) => " ".repeat(width),
+        };
+
+        step.repeat(self.groups)
+    }
+
+    /// Puts the prefix in front of every line of the message
+    fn indented(prefix: &str, message: &str) -> String {
+        if prefix.is_empty() {
+            return message.to_string();
+        }
+
+        message
+            .split('\n')
+            .map(|line| format!("{}{}", prefix, line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Output lines that are already colorized to stdout
+    fn try_block(&mut self, lines: Vec<String>) -> io::Result<&mut Self> {
+        self.try_done()?;
+        let block = Self::indented(&self.group_prefix(), &lines.join("\n"));
+        let line_ending = self.get_line_ending();
+
+        self.try_write_stdout(|out| output::try_write(out, block, &line_ending, false))?;
+        Ok(self)
+    }
+
+    /// Hands the buffer to the given closure if the logger
+    /// is buffered, a locked stdout otherwise
+    fn try_write_stdout<F>(&mut self, write: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut dyn Write) -> io::Result<()>,
+    {
+        match self.buffer.as_mut() {
+            Some(buffer) => write(buffer),
+            None => write(&mut io::stdout().lock()),
+        }
+    }
+
+    /// Ignores the result if the pipe was closed
+    /// on the other end, panics otherwise
+    fn ignore_broken_pipe(&mut self, result: io::Result<()>, stream: &str) -> &mut Self {
+        output::ignore_broken_pipe(result, stream);
         self
     }
 
@@ -323,9 +1161,21 @@ impl<'a> Logger<'a> {
     }
 }
 
+/// A single frame of the loading animation. Only the spinner is
+/// colorized here, the message already went through the formatter
+/// and doing it again would turn escaped keys back into real ones.
+fn loading_frame(frame: &str, message: &str) -> String {
+    format!(
+        "{} {}",
+        colorize_string(format!("<cyan>{}</>", frame)),
+        message
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::formatter::{pin_icon_set, IconSet};
     use std::{thread, time::Duration};
 
     #[test]
@@ -342,6 +1192,16 @@ mod tests {
             .error("Done loading instantly lol");
     }
 
+    #[test]
+    fn loading_keeps_escapes() {
+        let mut logger = Logger::new();
+        let message = logger.colorize("<<red>x").unwrap();
+
+        assert_eq!(loading_frame("⠋", &message), "\x1B[36m⠋\x1B[0m <red>x");
+
+        logger.loading("<<red>x").done();
+    }
+
     #[test]
     fn multiple_loading() {
         let mut logger = Logger::new();
@@ -397,6 +1257,256 @@ mod tests {
             .info("If it didn't crash it's fine");
     }
 
+    #[test]
+    fn try_variants_chain() -> io::Result<()> {
+        let mut logger = Logger::new();
+
+        logger
+            .try_info("Somebody")?
+            .try_error("Once")?
+            .try_warn("Told")?
+            .try_success("Me")?
+            .try_newline(1)?
+            .try_indent(1)?
+            .try_log("If it didn't error it's fine")?
+            .try_loading("Loading")?
+            .try_done()?;
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(not(feature = "timestamps"))]
+    fn table() {
+        use crate::Border;
+
+        let mut logger = Logger::buffered();
+        let mut table = Table::new();
+        table.row(vec!["<ok>ok</>", "done"]).border(Border::Ascii);
+
+        logger.add_style("ok", vec!["green"]).table(&table);
+
+        let written = String::from_utf8_lossy(logger.buffer.as_ref().unwrap().buffer());
+        assert_eq!(
+            written,
+            "+----+------+\n| \x1B[32mok\x1B[0m | done |\n+----+------+\n"
+        );
+    }
+
+    #[test]
+    fn wrap() {
+        let mut logger = Logger::new();
+        logger
+            .wrap(Wrap::Width(12))
+            .template(Level::Info, "<cyan>[INFO]</> {}");
+
+        assert_eq!(
+            logger.render(Some(Level::Info), "<red>one two three</>").unwrap(),
+            "\x1B[36m[INFO]\x1B[0m \x1B[31mone\x1B[0m\n       \x1B[31mtwo\x1B[0m\n       \x1B[31mthree\x1B[0m"
+        );
+        assert_eq!(
+            logger.render(None, "one two three").unwrap(),
+            "one two\nthree"
+        );
+
+        logger.wrap(Wrap::Off);
+        assert_eq!(
+            logger.render(None, "one two three").unwrap(),
+            "one two three"
+        );
+    }
+
+    #[test]
+    fn groups() {
+        let _icons = pin_icon_set(IconSet::Unicode);
+        let mut logger = Logger::buffered();
+        logger.group("outer").group("inner");
+
+        assert_eq!(logger.render(None, "a\nb").unwrap(), "    a\n    b");
+
+        logger.group_end().group_indent(3);
+        assert_eq!(logger.render(None, "a").unwrap(), "   a");
+
+        logger.group_guides(true);
+        assert_eq!(logger.render(None, "a").unwrap(), "│  a");
+
+        logger.group_end().group_end();
+        assert_eq!(logger.render(None, "a").unwrap(), "a");
+
+        let written = String::from_utf8_lossy(logger.buffer.as_ref().unwrap().buffer());
+        assert!(written.contains("outer\n"));
+        assert!(written.ends_with("  inner\n"));
+    }
+
+    #[test]
+    fn groups_and_wrapping() {
+        let _icons = pin_icon_set(IconSet::Unicode);
+        let mut logger = Logger::buffered();
+        logger
+            .wrap(Wrap::Width(10))
+            .template(Level::Info, "> {}")
+            .group_guides(true)
+            .group("title");
+
+        assert_eq!(
+            logger.render(Some(Level::Info), "one two three").unwrap(),
+            "│ > one\n│   two\n│   three"
+        );
+    }
+
+    #[test]
+    fn rules() {
+        let mut logger = Logger::buffered();
+        logger.wrap(Wrap::Width(12)).rule_line("=");
+
+        assert_eq!(logger.render_rule(None).unwrap(), "============");
+        assert_eq!(
+            logger.render_rule(Some("<red>ab</>")).unwrap(),
+            "==== \x1B[31mab\x1B[0m ===="
+        );
+        assert_eq!(logger.render_rule(Some("abc")).unwrap(), "=== abc ====");
+        assert_eq!(
+            logger
+                .render_rule(Some("<red>a title too long</>"))
+                .unwrap(),
+            " \x1B[31ma title t…\x1B[0m"
+        );
+
+        logger.rule_line("-=").rule_style("<blue>");
+        assert_eq!(
+            logger.render_rule(None).unwrap(),
+            "\x1B[34m-=-=-=-=-=-=\x1B[0m"
+        );
+
+        logger.rule_line("=").rule_style("").group("group");
+        assert_eq!(logger.render_rule(Some("a")).unwrap(), "=== a ====");
+
+        logger.header("hi");
+        let written = String::from_utf8_lossy(logger.buffer.as_ref().unwrap().buffer());
+        assert!(written.ends_with("  ==========\n      hi\n  ==========\n"));
+    }
+
+    #[test]
+    fn key_values() {
+        let mut logger = Logger::new();
+        logger.kv_style("", "");
+
+        assert_eq!(
+            logger
+                .render_kv(&[("Name", "paris"), ("Features", "macros\ntimestamps")])
+                .unwrap(),
+            vec!["Name:     paris", "Features: macros\n          timestamps"]
+        );
+
+        logger.kv_style("<bold>", "<red>");
+        assert_eq!(
+            logger.render_kv(&[("a", 1)]).unwrap(),
+            vec!["\x1B[1ma\x1B[0m: \x1B[31m1\x1B[0m"]
+        );
+
+        logger.kv_style("", "").wrap(Wrap::Width(12));
+        assert_eq!(
+            logger.render_kv(&[("Key", "one two three")]).unwrap(),
+            vec!["Key: one two\n     three"]
+        );
+    }
+
+    #[test]
+    fn buffered() {
+        let mut logger = Logger::buffered();
+
+        logger
+            .templates(Templates::plain())
+            .info("Sitting in the buffer")
+            .indent(1)
+            .newline(1)
+            .log("In order");
+
+        #[cfg(not(feature = "timestamps"))]
+        assert_eq!(
+            logger.buffer.as_ref().unwrap().buffer(),
+            b"Sitting in the buffer\n\t\nIn order\n"
+        );
+        assert!(!logger.buffer.as_ref().unwrap().buffer().is_empty());
+
+        logger.flush();
+        assert!(logger.buffer.as_ref().unwrap().buffer().is_empty());
+
+        logger
+            .success("Buffered again")
+            .error("Flushed before this");
+        assert!(logger.buffer.as_ref().unwrap().buffer().is_empty());
+    }
+
+    #[test]
+    fn sanitize() {
+        let mut logger = Logger::new();
+        logger.sanitize(Sanitize::Strip);
+
+        assert_eq!(
+            logger.formatter.colorize("<red>\x1B[2Jhi</>"),
+            "\x1B[31mhi\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn strict_flushes_before_warning() {
+        let mut logger = Logger::buffered();
+        logger.strict(true).log("Before the warning");
+
+        assert!(!logger.buffer.as_ref().unwrap().buffer().is_empty());
+
+        logger.colorize("<bule>typo</>").unwrap();
+        assert!(logger.buffer.as_ref().unwrap().buffer().is_empty());
+    }
+
+    #[test]
+    fn strict_warns_once_per_tag() {
+        let mut logger = Logger::new();
+
+        assert_eq!(
+            logger.unknown_tags("<bule>a</> <bule> <red>"),
+            vec!["<bule>"]
+        );
+        assert!(logger.unknown_tags("<bule>b</>").is_empty());
+        assert_eq!(
+            logger.unknown_tags("<gren>c</gren>"),
+            vec!["<gren>", "</gren>"]
+        );
+
+        logger.strict(true).info("<bule>only warned about once</>");
+    }
+
+    #[test]
+    fn templates() {
+        let mut logger = Logger::new();
+        logger
+            .templates(Templates::plain())
+            .template(Level::Error, "[{}]");
+
+        assert_eq!(logger.templates.render(Level::Info, "a"), "a");
+        assert_eq!(logger.templates.render(Level::Error, "a"), "[a]");
+    }
+
+    #[test]
+    fn theme() {
+        let theme: Theme = "lol = green\n[levels]\ninfo = <lol>i</> {}"
+            .parse()
+            .unwrap();
+        let mut logger = Logger::new();
+        logger.theme(&theme);
+
+        assert_eq!(logger.templates.render(Level::Info, "a"), "<lol>i</> a");
+        assert_eq!(
+            logger.templates.render(Level::Warn, "a"),
+            "<yellow><warn></> a"
+        );
+        assert_eq!(
+            logger.formatter.colorize("<lol>i</> a"),
+            "\x1B[32mi\x1B[0m a"
+        );
+    }
+
     #[test]
     fn add_style_works() {
         let mut logger = Logger::new();
@@ -10,9 +10,14 @@
 /// ```
 #[macro_export]
 macro_rules! log {
-    ($($arg:tt)*) => {
-        $crate::output::format_stdout(format!($($arg)*), "\n")
-    }
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {
+        $crate::output::pieces_stdout(None, &$crate::__pieces!($crate, $fmt $(, $arg)*), "\n")
+    };
+    ($fmt:expr $(, $($arg:tt)*)?) => {{
+        let recorder = $crate::formatter::Recorder::default();
+        let message = $crate::__record!(recorder, $fmt, [] $(, $($arg)*)?);
+        $crate::output::pieces_stdout(None, &[message], "\n")
+    }}
 }
 
 /// Adds an info icon to the log message,
@@ -26,9 +31,14 @@ macro_rules! log {
 /// ```
 #[macro_export]
 macro_rules! info {
-    ($($arg:tt)*) => {
-        $crate::output::format_stdout(format!("<cyan><info></> {}", format!($($arg)*)), "\n")
-    }
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {
+        $crate::output::pieces_stdout(Some($crate::Level::Info), &$crate::__pieces!($crate, $fmt $(, $arg)*), "\n")
+    };
+    ($fmt:expr $(, $($arg:tt)*)?) => {{
+        let recorder = $crate::formatter::Recorder::default();
+        let message = $crate::__record!(recorder, $fmt, [] $(, $($arg)*)?);
+        $crate::output::pieces_stdout(Some($crate::Level::Info), &[message], "\n")
+    }}
 }
 
 /// Adds an error icon to the log message,
@@ -42,9 +52,14 @@ macro_rules! info {
 /// ```
 #[macro_export]
 macro_rules! error {
-    ($($arg:tt)*) => {
-        $crate::output::format_stderr(format!("<red><cross></> {}", format!($($arg)*)), "\n")
-    }
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {
+        $crate::output::pieces_stderr(Some($crate::Level::Error), &$crate::__pieces!($crate, $fmt $(, $arg)*), "\n")
+    };
+    ($fmt:expr $(, $($arg:tt)*)?) => {{
+        let recorder = $crate::formatter::Recorder::default();
+        let message = $crate::__record!(recorder, $fmt, [] $(, $($arg)*)?);
+        $crate::output::pieces_stderr(Some($crate::Level::Error), &[message], "\n")
+    }}
 }
 
 /// Adds a warning icon to the log message,
@@ -58,9 +73,14 @@ macro_rules! error {
 /// ```
 #[macro_export]
 macro_rules! warn {
-    ($($arg:tt)*) => {
-        $crate::output::format_stdout(format!("<yellow><warn></> {}", format!($($arg)*)), "\n")
-    }
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {
+        $crate::output::pieces_stdout(Some($crate::Level::Warn), &$crate::__pieces!($crate, $fmt $(, $arg)*), "\n")
+    };
+    ($fmt:expr $(, $($arg:tt)*)?) => {{
+        let recorder = $crate::formatter::Recorder::default();
+        let message = $crate::__record!(recorder, $fmt, [] $(, $($arg)*)?);
+        $crate::output::pieces_stdout(Some($crate::Level::Warn), &[message], "\n")
+    }}
 }
 
 /// Adds a success icon to the log message,
@@ -74,15 +94,259 @@ macro_rules! warn {
 /// ```
 #[macro_export]
 macro_rules! success {
-    ($($arg:tt)*) => {
-        $crate::output::format_stdout(format!("<green><tick></> {}", format!($($arg)*)), "\n")
-    }
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {
+        $crate::output::pieces_stdout(Some($crate::Level::Success), &$crate::__pieces!($crate, $fmt $(, $arg)*), "\n")
+    };
+    ($fmt:expr $(, $($arg:tt)*)?) => {{
+        let recorder = $crate::formatter::Recorder::default();
+        let message = $crate::__record!(recorder, $fmt, [] $(, $($arg)*)?);
+        $crate::output::pieces_stdout(Some($crate::Level::Success), &[message], "\n")
+    }}
+}
+
+/// Formats a non-literal format string through the recorder,
+/// wrapping every argument, named or not, one at a time
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __record {
+    ($recorder:ident, $fmt:expr, [$($done:tt)*] $(,)?) => {
+        $recorder.record(format_args!($fmt $($done)*))
+    };
+    ($recorder:ident, $fmt:expr, [$($done:tt)*], $name:ident = $arg:expr $(, $($rest:tt)*)?) => {
+        $crate::__record!($recorder, $fmt, [$($done)*, $name = $recorder.arg(&$arg)] $(, $($rest)*)?)
+    };
+    ($recorder:ident, $fmt:expr, [$($done:tt)*], $arg:expr $(, $($rest:tt)*)?) => {
+        $crate::__record!($recorder, $fmt, [$($done)*, $recorder.arg(&$arg)] $(, $($rest)*)?)
+    };
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::formatter::{colorize_string, tables, write_pieces, Recorder, Sanitize};
+
+    /// Makes sure breaking things down at compile time ends
+    /// up with exactly what the formatter would do at runtime
+    macro_rules! same_as_runtime {
+        ($($arg:tt)*) => {{
+            let mut compiled = String::new();
+            write_pieces(("", ""), &crate::__pieces!(crate, $($arg)*), (false, Sanitize::Off), &mut compiled).unwrap();
+
+            assert_eq!(compiled, colorize_string(format!($($arg)*)));
+        }};
+    }
+
+    #[test]
+    fn compiled_tags() {
+        same_as_runtime!("<black><red><green><yellow><blue><magenta><cyan><white>");
+        same_as_runtime!("<bright-black><bright_red><bright green><bright-white>");
+        same_as_runtime!("<on-black><on_red><on green><on-bright-yellow>");
+        same_as_runtime!("<bold><b><dimmed><d><italic><i><underline><u><blink><l>");
+        same_as_runtime!("<reverse><r><hidden><h><strikethrough><s>");
+        same_as_runtime!("</bold></b></dimmed></i></u></l></r></h></s>");
+        same_as_runtime!("<info><cross><warn><tick><heart><warning>");
+        same_as_runtime!("<:rocket:> <emoji-tada> <:-1:>");
+        same_as_runtime!("<arrow-right> <pointer_small> <star> <ellipsis> <line double>");
+        same_as_runtime!("</><//><///>");
+        same_as_runtime!("<red>a <b>b</bold> c</red> <on-blue>unclosed");
+        same_as_runtime!("<bold red on-black>a</bold red on-black> <red+b>b</red+b>");
+        same_as_runtime!("<u on bright blue>c</> <on-bright-red+i>d");
+    }
+
+    #[test]
+    fn compiled_tables() {
+        // Compiled and checked against the runtime while
+        // getting the keys to make sure none are missing
+        macro_rules! compiled {
+            ($keys:literal) => {{
+                same_as_runtime!($keys);
+                $keys
+            }};
+        }
+
+        let every_key = compiled!(
+            "<bold><b></bold></b><dimmed><d></dimmed></d><italic><i></italic></i>\
+             <underline><u></underline></u><blink><l></blink></l><reverse><r>\
+             </reverse></r><hidden><h></hidden></h><strikethrough><s>\
+             </strikethrough></s><black><red><green><yellow><blue><magenta><cyan>\
+             <white><bright black><bright red><bright green><bright yellow>\
+             <bright blue><bright magenta><bright cyan><bright white><on black>\
+             <on red><on green><on yellow><on blue><on magenta><on cyan><on white>\
+             <on bright black><on bright red><on bright green><on bright yellow>\
+             <on bright blue><on bright magenta><on bright cyan><on bright white>\
+             <///><//></><info><cross><warn><warning><tick><heart><square>\
+             <square small><square small filled><square dark shade>\
+             <square medium shade><square light shade><square top><square bottom>\
+             <square left><square right><square center><circle><circle filled>\
+             <circle dotted><circle double><circle circle><circle cross>\
+             <circle pipe><circle question mark><radio on><radio off><checkbox on>\
+             <checkbox off><checkbox circle on><checkbox circle off>\
+             <question mark prefix><bullet><dot><ellipsis><pointer><pointer small>\
+             <triangle up><triangle down><triangle left><triangle right><lozenge>\
+             <lozenge outline><home><hamburger><smiley><mustache><star><play>\
+             <music note><music note beamed><nodejs><arrow up><arrow down>\
+             <arrow left><arrow right><arrow left right><arrow up down>\
+             <almost equal><not equal><less or equal><greater or equal><identical>\
+             <infinity><subscript zero><subscript one><subscript two>\
+             <subscript three><subscript four><subscript five><subscript six>\
+             <subscript seven><subscript eight><subscript nine><one half>\
+             <one third><one quarter><one fifth><one sixth><one seventh>\
+             <one eighth><one ninth><one tenth><two thirds><two fifths>\
+             <three quarters><three fifths><three eighths><four fifths>\
+             <five sixths><five eighths><seven eighths><line><line bold>\
+             <line double><line vertical><line vertical bold><line vertical double>"
+        );
+        for (name, _) in tables::STYLES.iter().chain(tables::RESETS) {
+            assert!(
+                every_key.contains(&format!("<{}>", name)),
+                "{} is missing",
+                name
+            );
+        }
+
+        for (name, _) in tables::COLORS {
+            assert!(
+                every_key.contains(&format!("<{}>", name)),
+                "{} is missing",
+                name
+            );
+            assert!(
+                every_key.contains(&format!("<on {}>", name)),
+                "on {} is missing",
+                name
+            );
+        }
+
+        for name in tables::LOG_ICONS {
+            assert!(
+                every_key.contains(&format!("<{}>", name)),
+                "{} is missing",
+                name
+            );
+        }
+
+        for (name, _, _) in tables::FIGURES {
+            assert!(
+                every_key.contains(&format!("<{}>", name)),
+                "{} is missing",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn custom_icons_replace_compiled_keys() {
+        crate::formatter::add_icon("magenta+hidden", "M");
+
+        same_as_runtime!("<magenta+hidden> icon");
+        assert_eq!(colorize_string("<magenta+hidden>"), "M");
+    }
+
+    #[test]
+    fn compiled_text() {
+        same_as_runtime!("<---- <red>x</> <unterminated");
+        same_as_runtime!("<< powering on 'TV' (0) {{<red>}}");
+        same_as_runtime!("ℹ <bold>unicode ✔</>");
+        same_as_runtime!("<<red> is how you write <<<b>{}<<</b>", "<<escaped>");
+        same_as_runtime!("a << b, cat <<EOF and x <<{}>", "red");
+    }
+
+    #[test]
+    #[allow(deprecated)] // Unknown tags warn at compile time
+    fn compiled_unknown_tags() {
+        same_as_runtime!("<html> This is normal stuff </html> <bule>typo</bule>");
+    }
+
+    #[test]
+    fn verbatim_arguments() {
+        let pieces = crate::__pieces!(crate, "<red>{}</> {}", "<blue>input</>", 1);
+        let mut output = String::new();
+        write_pieces(("", ""), &pieces, (true, Sanitize::Off), &mut output).unwrap();
+
+        assert_eq!(output, "\x1B[31m<blue>input</>\x1B[0m 1");
+    }
+
+    #[test]
+    fn sanitized_arguments() {
+        let pieces = crate::__pieces!(crate, "<red>{}</>", "\x1B[2J<b>a</>");
+        let mut output = String::new();
+        write_pieces(("", ""), &pieces, (true, Sanitize::Strip), &mut output).unwrap();
+
+        assert_eq!(output, "\x1B[31m<b>a</>\x1B[0m");
+
+        let recorder = Recorder::default();
+        let pieces = [recorder.record(format_args!(
+            concat!("\x1B[1m{}", "</>"),
+            recorder.arg(&"\x1B[2J<b>a</>")
+        ))];
+        let mut output = String::new();
+        write_pieces(("", ""), &pieces, (false, Sanitize::Escape), &mut output).unwrap();
+
+        assert_eq!(output, "\x1B[1m\\x1b[2J\x1B[1ma\x1B[0m\x1B[0m");
+    }
+
+    #[test]
+    fn runtime_format_strings() {
+        let render = |verbatim| {
+            let recorder = Recorder::default();
+            let pieces = [recorder.record(format_args!(
+                concat!("<red>{}", "</> {:>4} <{}>"),
+                recorder.arg(&"<b>a</>"),
+                recorder.arg(&1),
+                recorder.arg(&"blue"),
+            ))];
+
+            let mut output = String::new();
+            write_pieces(("", ""), &pieces, (verbatim, Sanitize::Off), &mut output).unwrap();
+            output
+        };
+
+        assert_eq!(
+            render(false),
+            colorize_string("<red><b>a</></>    1 <blue>")
+        );
+        assert_eq!(render(true), "\x1B[31m<b>a</>\x1B[0m    1 <blue>");
+    }
+
+    #[test]
+    fn runtime_named_arguments() {
+        let recorder = Recorder::default();
+        let n = 2;
+        let pieces = [crate::__record!(
+            recorder,
+            concat!("{} <{color}>{n}", "</> {x:>3}"),
+            [],
+            "<b>a</>",
+            color = "green",
+            n = n,
+            x = n + 1,
+        )];
+
+        let mut output = String::new();
+        write_pieces(("", ""), &pieces, (false, Sanitize::Off), &mut output).unwrap();
+
+        assert_eq!(output, colorize_string("<b>a</> <green>2</>   3"));
+    }
+
+    #[test]
+    fn compiled_arguments() {
+        let x = 5;
+        let width = 6;
+
+        same_as_runtime!("<red>{}</> {:>5} {:<3$}|", "a", "b", "c", 4);
+        same_as_runtime!(
+            "{x} {:>width$} {0:?} {name}",
+            "<blue>c</>",
+            name = "<b>d</>"
+        );
+        same_as_runtime!("{:.*} {:+08.2}", 2, 1.23456, 2.5);
+        same_as_runtime!("<{}>dynamic</> <on-{}>tags</>", "green", "red");
+        same_as_runtime!("<red>{}</red>", "<b>closed</b> inside");
+    }
+
     #[test]
     fn macros() {
+        let _lock = crate::output::lock_settings();
+
         log!("This <cyan>is <bright green>a log<//>!");
         info!(
             "<red>HAHAHAHAHA<///> <black><on green>{}</>",
@@ -92,6 +356,10 @@ mod tests {
         warn!("This is a {} <yellow>BEWARE</>!", "warning");
         success!("{} went well, congrats!", "<bright green>Everything</>");
 
+        info!(concat!("<red>", "{}", "</> from concat!"), "Built");
+        log!(concat!("<bold>", "no arguments", "</>"),);
+        success!(concat!("{n} ", "<green>{}</>"), "named", n = 1);
+
         match "a" {
             "a" => log!(
                 "It works inside a match as well!!! {}",
@@ -1,11 +1,145 @@
 //! Helper functions for writing to stdout/stderr
 //!
-//! Some can format, some cannot
+//! Some can format, some cannot. Every writer comes in
+//! two flavours, a `try_*` one that hands you the `io::Result`
+//! and a plain one that quietly gives up when the other end of
+//! the pipe has been closed (think `mycli | head -1`) instead of panicking.
+mod terminal;
+pub use terminal::terminal_width;
+
 #[cfg(any(feature = "macros", not(feature = "no_logger")))]
 use std::fmt::Display;
+use std::io;
+#[cfg(any(feature = "macros", not(feature = "no_logger")))]
+use std::io::prelude::*;
+
+#[cfg(any(feature = "macros", not(feature = "no_logger")))]
+use crate::formatter::Ansi;
+#[cfg(feature = "macros")]
+use crate::formatter::{self, Piece, Sanitize};
+#[cfg(feature = "macros")]
+use crate::{Level, Templates};
+#[cfg(feature = "macros")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "macros")]
+use std::sync::RwLock;
+
+/// Whether arguments given to the macros should
+/// be written out as is, without replacing keys
+#[cfg(feature = "macros")]
+static VERBATIM_ARGUMENTS: AtomicBool = AtomicBool::new(false);
+
+/// What the macros do with control characters in their arguments
+#[cfg(feature = "macros")]
+static SANITIZE_ARGUMENTS: RwLock<Sanitize> = RwLock::new(Sanitize::Off);
+
+/// Templates used by the macros, the default ones if not set
+#[cfg(feature = "macros")]
+static TEMPLATES: RwLock<Option<Templates>> = RwLock::new(None);
+
+/// How the macros treat the arguments they're given
+#[cfg(feature = "macros")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Keys inside arguments are replaced just like the ones
+    /// in the format string. This is the default.
+    Tagged,
+
+    /// Arguments are written out exactly as they are, only keys
+    /// in the format string itself are replaced. Use this when logging
+    /// things you don't control, along with [`set_sanitize`](fn.set_sanitize.html)
+    /// since raw escape sequences in them still reach the terminal.
+    Verbatim,
+}
+
+/// Sets how all macros treat their arguments from now on. This only
+/// decides whether keys in them are replaced, escape sequences like
+/// `\x1B[2J` are left to [`set_sanitize`](fn.set_sanitize.html).
+///
+/// # Example
+/// ```
+/// use paris::info;
+/// use paris::output::{set_interpolation, Interpolation};
+///
+/// set_interpolation(Interpolation::Verbatim);
+///
+/// let user_input = "<red>not red</>";
+/// info!("<blue>{}</>", user_input); // Only the blue is replaced
+/// # set_interpolation(Interpolation::Tagged);
+/// ```
+#[cfg(feature = "macros")]
+pub fn set_interpolation(mode: Interpolation) {
+    VERBATIM_ARGUMENTS.store(mode == Interpolation::Verbatim, Ordering::Relaxed);
+}
 
+/// How the macros currently treat their arguments
 #[cfg(feature = "macros")]
-use crate::formatter;
+pub fn interpolation() -> Interpolation {
+    if VERBATIM_ARGUMENTS.load(Ordering::Relaxed) {
+        return Interpolation::Verbatim;
+    }
+
+    Interpolation::Tagged
+}
+
+/// Sets what all macros do with control characters and raw escape
+/// sequences in their arguments from now on. The format string
+/// itself is never touched.
+///
+/// # Example
+/// ```
+/// use paris::info;
+/// use paris::formatter::Sanitize;
+/// use paris::output::{set_interpolation, set_sanitize, Interpolation};
+///
+/// set_interpolation(Interpolation::Verbatim);
+/// set_sanitize(Sanitize::Strip);
+///
+/// let user_input = "<red>\x1B[2Jstill here</>";
+/// info!("<blue>{}</>", user_input); // No tags, no cleared screen
+/// # set_interpolation(Interpolation::Tagged);
+/// # set_sanitize(Sanitize::Off);
+/// ```
+#[cfg(feature = "macros")]
+pub fn set_sanitize(mode: Sanitize) {
+    *SANITIZE_ARGUMENTS
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = mode;
+}
+
+/// What the macros currently do with control characters in their arguments
+#[cfg(feature = "macros")]
+pub fn sanitize_mode() -> Sanitize {
+    *SANITIZE_ARGUMENTS.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Sets what the level macros (`info!`, `warn!`, etc.) look like from now on
+///
+/// # Example
+/// ```
+/// use paris::info;
+/// use paris::output::set_templates;
+/// use paris::Templates;
+///
+/// set_templates(Templates::labels());
+///
+/// info!("Starts with [INFO] instead of an icon");
+/// # set_templates(Templates::icons());
+/// ```
+#[cfg(feature = "macros")]
+pub fn set_templates(templates: Templates) {
+    *TEMPLATES.write().unwrap_or_else(|e| e.into_inner()) = Some(templates);
+}
+
+/// What the level macros currently look like
+#[cfg(feature = "macros")]
+pub fn templates() -> Templates {
+    TEMPLATES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
 
 /// Gets the current timestamp or empty string
 /// based on whether timestamps feature is enabled
@@ -22,21 +156,24 @@ fn current_time() -> String {
     }
 }
 
+/// Swallows broken pipe errors since there's nobody
+/// left to read the output anyway. Any other error
+/// panics, same as `print!` would.
+pub(crate) fn ignore_broken_pipe(result: io::Result<()>, stream: &str) {
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(e) => panic!("failed printing to {}: {}", stream, e),
+        Ok(_) => {}
+    }
+}
+
 /// Writes to stdout without replacing keys
 #[cfg(not(feature = "no_logger"))]
 pub fn stdout<T>(message: T, line_ending: &str, with_carriage: bool)
 where
     T: Display,
 {
-    let mut carriage = "";
-
-    if with_carriage {
-        carriage = "\r";
-    }
-
-    let timestamp = current_time();
-    let message = format!("{}{}{}{}", carriage, timestamp, message, line_ending);
-    print!("{}", message);
+    ignore_broken_pipe(try_stdout(message, line_ending, with_carriage), "stdout");
 }
 
 /// Writes to stderr without replacing keys
@@ -45,15 +182,37 @@ pub fn stderr<T>(message: T, line_ending: &str, with_carriage: bool)
 where
     T: Display,
 {
-    let mut carriage = "";
+    ignore_broken_pipe(try_stderr(message, line_ending, with_carriage), "stderr");
+}
 
-    if with_carriage {
-        carriage = "\r";
-    }
+/// Writes to stdout without replacing keys,
+/// returns any error that happened while writing
+#[cfg(not(feature = "no_logger"))]
+pub fn try_stdout<T>(message: T, line_ending: &str, with_carriage: bool) -> io::Result<()>
+where
+    T: Display,
+{
+    try_write(
+        &mut io::stdout().lock(),
+        message,
+        line_ending,
+        with_carriage,
+    )
+}
 
-    let timestamp = current_time();
-    let message = format!("{}{}{}{}", carriage, timestamp, message, line_ending);
-    eprint!("{}", message);
+/// Writes to stderr without replacing keys,
+/// returns any error that happened while writing
+#[cfg(not(feature = "no_logger"))]
+pub fn try_stderr<T>(message: T, line_ending: &str, with_carriage: bool) -> io::Result<()>
+where
+    T: Display,
+{
+    try_write(
+        &mut io::stderr().lock(),
+        message,
+        line_ending,
+        with_carriage,
+    )
 }
 
 /// Writes to stdout and replaces keys inside the given string
@@ -62,9 +221,7 @@ pub fn format_stdout<T>(message: T, line_ending: &str)
 where
     T: Display,
 {
-    let timestamp = current_time();
-    let message = format!("{}{}{}", timestamp, message, line_ending);
-    print!("{}", formatter::colorize_string(message));
+    ignore_broken_pipe(try_format_stdout(message, line_ending), "stdout");
 }
 
 /// Writes to stderr and replaces keys inside the given string
@@ -73,7 +230,199 @@ pub fn format_stderr<T>(message: T, line_ending: &str)
 where
     T: Display,
 {
+    ignore_broken_pipe(try_format_stderr(message, line_ending), "stderr");
+}
+
+/// Writes to stdout and replaces keys inside the given string,
+/// returns any error that happened while writing
+#[cfg(feature = "macros")]
+pub fn try_format_stdout<T>(message: T, line_ending: &str) -> io::Result<()>
+where
+    T: Display,
+{
+    let message = formatter::colorize_string(message.to_string());
+    try_write(&mut io::stdout().lock(), message, line_ending, false)
+}
+
+/// Writes to stderr and replaces keys inside the given string,
+/// returns any error that happened while writing
+#[cfg(feature = "macros")]
+pub fn try_format_stderr<T>(message: T, line_ending: &str) -> io::Result<()>
+where
+    T: Display,
+{
+    let message = formatter::colorize_string(message.to_string());
+    try_write(&mut io::stderr().lock(), message, line_ending, false)
+}
+
+/// Writes pieces the macros broke down at compile time to stdout
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub fn pieces_stdout(level: Option<Level>, pieces: &[Piece], line_ending: &str) {
+    let message = render(level, pieces);
+    let result = try_write(&mut io::stdout().lock(), message, line_ending, false);
+    ignore_broken_pipe(result, "stdout");
+}
+
+/// Writes pieces the macros broke down at compile time to stderr
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub fn pieces_stderr(level: Option<Level>, pieces: &[Piece], line_ending: &str) {
+    let message = render(level, pieces);
+    let result = try_write(&mut io::stderr().lock(), message, line_ending, false);
+    ignore_broken_pipe(result, "stderr");
+}
+
+/// Puts the pieces back together into a message,
+/// inside the template of the level if there is one
+#[cfg(feature = "macros")]
+fn render(level: Option<Level>, pieces: &[Piece]) -> String {
+    let mut message = String::new();
+    let templates = templates();
+
+    let around = match level {
+        Some(level) => templates.split(level),
+        None => ("", ""),
+    };
+
+    // Writing to a string can't fail
+    let verbatim = interpolation() == Interpolation::Verbatim;
+    let _ = formatter::write_pieces(around, pieces, (verbatim, sanitize_mode()), &mut message);
+    message
+}
+
+/// Writes the timestamp, message and line ending
+/// to the given writer in one go, without replacing keys.
+///
+/// If the message leaves any color or style active, it's reset
+/// before the line ending so it doesn't bleed into the next line.
+#[cfg(any(feature = "macros", not(feature = "no_logger")))]
+pub fn try_write<W, T>(
+    out: &mut W,
+    message: T,
+    line_ending: &str,
+    with_carriage: bool,
+) -> io::Result<()>
+where
+    W: Write + ?Sized,
+    T: Display,
+{
+    let carriage = if with_carriage { "\r" } else { "" };
     let timestamp = current_time();
-    let message = format!("{}{}{}", timestamp, message, line_ending);
-    eprint!("{}", formatter::colorize_string(message));
+    let message = message.to_string();
+
+    let reset = match Ansi::leaves_style(&message) {
+        Some(true) => "\x1B[0m",
+        _ => "",
+    };
+
+    write!(
+        out,
+        "{}{}{}{}{}",
+        carriage, timestamp, message, reset, line_ending
+    )
+}
+
+/// Tests that change or depend on any of the global settings
+/// have to take turns, the rest of the suite runs in parallel
+#[cfg(test)]
+pub(crate) fn lock_settings() -> std::sync::MutexGuard<'static, ()> {
+    static SETTINGS: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    SETTINGS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Puts the macro settings back the way they were,
+    /// even if the test using them fails halfway through
+    #[cfg(feature = "macros")]
+    struct Defaults;
+
+    #[cfg(feature = "macros")]
+    impl Drop for Defaults {
+        fn drop(&mut self) {
+            set_interpolation(Interpolation::Tagged);
+            set_sanitize(Sanitize::Off);
+            set_templates(Templates::default());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "macros")]
+    fn interpolation_mode() {
+        let _lock = lock_settings();
+        let _defaults = Defaults;
+
+        assert_eq!(interpolation(), Interpolation::Tagged);
+
+        set_interpolation(Interpolation::Verbatim);
+        assert_eq!(interpolation(), Interpolation::Verbatim);
+
+        set_interpolation(Interpolation::Tagged);
+        assert_eq!(interpolation(), Interpolation::Tagged);
+
+        set_sanitize(Sanitize::Strip);
+        assert_eq!(render(None, &[Piece::Arg(String::from("\x1B[2Jhi"))]), "hi");
+    }
+
+    #[test]
+    #[cfg(feature = "macros")]
+    fn macro_templates() {
+        let _lock = lock_settings();
+        let _defaults = Defaults;
+        let pieces = [Piece::Text("hi")];
+        crate::formatter::set_icon_set(crate::formatter::IconSet::Unicode);
+
+        assert_eq!(render(Some(Level::Info), &pieces), "\x1B[36mℹ\x1B[0m hi");
+        assert_eq!(render(None, &pieces), "hi");
+
+        let mut labels = Templates::labels();
+        labels.set(Level::Warn, "<yellow>{}");
+        set_templates(labels);
+
+        assert_eq!(
+            render(Some(Level::Error), &pieces),
+            "\x1B[31m[ERROR]\x1B[0m   hi"
+        );
+        assert_eq!(render(Some(Level::Warn), &pieces), "\x1B[33mhi\x1B[0m");
+
+        set_templates(Templates::icons());
+        assert_eq!(templates(), Templates::default());
+    }
+
+    #[test]
+    #[cfg(all(
+        not(feature = "timestamps"),
+        any(feature = "macros", not(feature = "no_logger"))
+    ))]
+    fn resets_active_styles() {
+        let mut out = vec![];
+
+        try_write(&mut out, "\x1B[31mred", "\n", false).unwrap();
+        try_write(&mut out, "\x1B[31mred\x1B[0m", "\n", false).unwrap();
+        try_write(&mut out, "plain", "\n", false).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1B[31mred\x1B[0m\n\x1B[31mred\x1B[0m\nplain\n"
+        );
+    }
+
+    #[test]
+    fn broken_pipe_is_ignored() {
+        let err = io::Error::from(io::ErrorKind::BrokenPipe);
+
+        ignore_broken_pipe(Err(err), "stdout");
+    }
+
+    #[test]
+    #[should_panic(expected = "failed printing to stdout")]
+    fn other_errors_panic() {
+        let err = io::Error::from(io::ErrorKind::PermissionDenied);
+
+        ignore_broken_pipe(Err(err), "stdout");
+    }
 }
@@ -0,0 +1,32 @@
+//! Asking the terminal how big it is
+
+/// Width of the terminal in columns. `COLUMNS` wins if it's set,
+/// otherwise the terminal stdout is attached to gets asked, as long
+/// as the `terminal_size` feature is on (it is by default).
+/// Nothing if neither of them knows, like when stdout isn't a terminal.
+///
+/// # Example
+/// ```
+/// use paris::output::terminal_width;
+///
+/// let width = terminal_width().unwrap_or(80);
+/// ```
+pub fn terminal_width() -> Option<usize> {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse().ok())
+        .filter(|&columns| columns > 0)
+        .or_else(stdout_width)
+}
+
+#[cfg(feature = "terminal_size")]
+fn stdout_width() -> Option<usize> {
+    let (terminal_size::Width(columns), _) = terminal_size::terminal_size()?;
+
+    Some(columns as usize).filter(|&columns| columns > 0)
+}
+
+#[cfg(not(feature = "terminal_size"))]
+fn stdout_width() -> Option<usize> {
+    None
+}
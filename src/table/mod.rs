@@ -0,0 +1,359 @@
+//! Tables with aligned columns, cells can have keys in them
+//!
+//! # Example
+//! ```
+//! use paris::{Align, Border, Table};
+//!
+//! let mut table = Table::new();
+//! table
+//!     .headers(vec!["<bold>Crate</>", "<bold>Status</>"])
+//!     .row(vec!["paris", "<green>passing</>"])
+//!     .row(vec!["colored", "<red>failing</>"])
+//!     .align(1, Align::Right)
+//!     .border(Border::Rounded);
+//!
+//! println!("{}", table);
+//! ```
+use crate::formatter::{self, width, Align, IconSet};
+use std::fmt::{self, Display, Formatter};
+
+/// What the lines around and between cells are drawn with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Border {
+    /// Columns are only separated by spaces
+    None,
+
+    /// `+`, `-` and `|`, for terminals that can't show anything else
+    Ascii,
+
+    /// `┌─┐`, this is the default unless icons are
+    /// drawn with [`IconSet::Ascii`](formatter/enum.IconSet.html)
+    Single,
+
+    /// `╭─╮`
+    Rounded,
+
+    /// `╔═╗`
+    Double,
+
+    /// `┏━┓`
+    Heavy,
+}
+
+/// Everything a border is made of, corners and crossings
+/// go top to bottom, left to right
+pub(crate) struct Lines {
+    pub horizontal: &'static str,
+    pub vertical: &'static str,
+    pub top: [&'static str; 3],
+    pub middle: [&'static str; 3],
+    pub bottom: [&'static str; 3],
+}
+
+impl Default for Border {
+    /// `Single`, or `Ascii` if that's what icons are drawn with
+    fn default() -> Self {
+        Self::for_icon_set(formatter::icon_set())
+    }
+}
+
+impl Border {
+    fn for_icon_set(set: IconSet) -> Self {
+        match set {
+            IconSet::Unicode => Border::Single,
+            IconSet::Ascii => Border::Ascii,
+        }
+    }
+
+    /// The lines this border draws with, nothing for `Border::None`
+    pub(crate) fn lines(self) -> Option<Lines> {
+        let lines = match self {
+            Border::None => return None,
+            Border::Ascii => Lines {
+                horizontal: "-",
+                vertical: "|",
+                top: ["+", "+", "+"],
+                middle: ["+", "+", "+"],
+                bottom: ["+", "+", "+"],
+            },
+            Border::Single => Lines {
+                horizontal: "─",
+                vertical: "│",
+                top: ["┌", "┬", "┐"],
+                middle: ["├", "┼", "┤"],
+                bottom: ["└", "┴", "┘"],
+            },
+            Border::Rounded => Lines {
+                horizontal: "─",
+                vertical: "│",
+                top: ["╭", "┬", "╮"],
+                middle: ["├", "┼", "┤"],
+                bottom: ["╰", "┴", "╯"],
+            },
+            Border::Double => Lines {
+                horizontal: "═",
+                vertical: "║",
+                top: ["╔", "╦", "╗"],
+                middle: ["╠", "╬", "╣"],
+                bottom: ["╚", "╩", "╝"],
+            },
+            Border::Heavy => Lines {
+                horizontal: "━",
+                vertical: "┃",
+                top: ["┏", "┳", "┓"],
+                middle: ["┣", "╋", "┫"],
+                bottom: ["┗", "┻", "┛"],
+            },
+        };
+
+        Some(lines)
+    }
+}
+
+/// Rows of cells lined up in columns. Widths are worked out
+/// from what ends up on screen, so keys, escape sequences and
+/// wide characters don't throw the columns off.
+#[derive(Clone, Debug, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    aligns: Vec<Align>,
+    border: Option<Border>,
+}
+
+impl Table {
+    /// Creates an empty table with the default border
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the headers, they get a line under them
+    pub fn headers<I, T>(&mut self, headers: I) -> &mut Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Display,
+    {
+        self.headers = headers.into_iter().map(|h| h.to_string()).collect();
+        self
+    }
+
+    /// Adds a row. Rows don't need to have the same amount
+    /// of cells, missing ones are left empty.
+    pub fn row<I, T>(&mut self, row: I) -> &mut Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Display,
+    {
+        self.rows
+            .push(row.into_iter().map(|c| c.to_string()).collect());
+        self
+    }
+
+    /// Aligns every cell in the given column, starting at 0.
+    /// Columns are aligned left unless told otherwise.
+    pub fn align(&mut self, column: usize, align: Align) -> &mut Self {
+        if self.aligns.len() <= column {
+            self.aligns.resize(column + 1, Align::Left);
+        }
+
+        self.aligns[column] = align;
+        self
+    }
+
+    /// Sets what the lines are drawn with
+    pub fn border(&mut self, border: Border) -> &mut Self {
+        self.border = Some(border);
+        self
+    }
+
+    /// Every line of the table, with keys replaced by `colorize`
+    pub(crate) fn lines<F>(&self, mut colorize: F) -> Vec<String>
+    where
+        F: FnMut(&str) -> String,
+    {
+        let mut render = |row: &Vec<String>| row.iter().map(|c| colorize(c)).collect::<Vec<_>>();
+
+        let headers = render(&self.headers);
+        let rows: Vec<Vec<String>> = self.rows.iter().map(&mut render).collect();
+
+        let columns = rows
+            .iter()
+            .map(Vec::len)
+            .chain(Some(headers.len()))
+            .max()
+            .unwrap_or(0);
+
+        let mut widths = vec![0; columns];
+
+        for row in rows.iter().chain(Some(&headers)) {
+            for (idx, cell) in row.iter().enumerate() {
+                widths[idx] = widths[idx].max(width::width(cell));
+            }
+        }
+
+        let mut lines = vec![];
+        let border = self.border.unwrap_or_default().lines();
+
+        if let Some(border) = border.as_ref() {
+            lines.push(self.separator(border, &border.top, &widths));
+        }
+
+        if !headers.is_empty() {
+            lines.push(self.row_line(border.as_ref(), &headers, &widths));
+
+            if let Some(border) = border.as_ref() {
+                lines.push(self.separator(border, &border.middle, &widths));
+            }
+        }
+
+        for row in rows.iter() {
+            lines.push(self.row_line(border.as_ref(), row, &widths));
+        }
+
+        if let Some(border) = border.as_ref() {
+            lines.push(self.separator(border, &border.bottom, &widths));
+        }
+
+        lines
+    }
+
+    /// A line of cells, padded to their column's width
+    fn row_line(&self, border: Option<&Lines>, row: &[String], widths: &[usize]) -> String {
+        let cells: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(idx, width)| {
+                let cell = row.get(idx).map(String::as_str).unwrap_or("");
+                let align = self.aligns.get(idx).copied().unwrap_or_default();
+
+                width::align(cell, *width, align)
+            })
+            .collect();
+
+        match border {
+            Some(border) => format!(
+                "{v} {} {v}",
+                cells.join(&format!(" {} ", border.vertical)),
+                v = border.vertical
+            ),
+            None => cells.join("  ").trim_end().to_string(),
+        }
+    }
+
+    /// A horizontal line with the given corners and crossings
+    fn separator(
+        &self,
+        border: &Lines,
+        [left, cross, right]: &[&str; 3],
+        widths: &[usize],
+    ) -> String {
+        let parts: Vec<String> = widths
+            .iter()
+            .map(|width| border.horizontal.repeat(width + 2))
+            .collect();
+
+        format!("{}{}{}", left, parts.join(cross), right)
+    }
+}
+
+impl Display for Table {
+    /// Writes the table with keys replaced, without custom styles.
+    /// Use `Logger::table` for those.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let lines = self.lines(|cell| formatter::colorize_string(cell));
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::{pin_icon_set, IconSet};
+
+    fn plain(table: &Table) -> String {
+        table
+            .lines(|cell| formatter::format_string(cell, false))
+            .join("\n")
+    }
+
+    #[test]
+    fn single_border() {
+        let _icons = pin_icon_set(IconSet::Unicode);
+        let mut table = Table::new();
+        table
+            .headers(vec!["name", "n"])
+            .row(vec!["a", "1"])
+            .row(vec!["<red>longer</>", "22"]);
+
+        assert_eq!(
+            plain(&table),
+            "┌────────┬────┐\n\
+             │ name   │ n  │\n\
+             ├────────┼────┤\n\
+             │ a      │ 1  │\n\
+             │ longer │ 22 │\n\
+             └────────┴────┘"
+        );
+    }
+
+    #[test]
+    fn alignment_and_missing_cells() {
+        let mut table = Table::new();
+        table
+            .row(vec!["abc", "1", "x"])
+            .row(vec!["d"])
+            .align(1, Align::Right)
+            .align(2, Align::Center)
+            .border(Border::Ascii);
+
+        assert_eq!(
+            plain(&table),
+            "+-----+---+---+\n\
+             | abc | 1 | x |\n\
+             | d   |   |   |\n\
+             +-----+---+---+"
+        );
+    }
+
+    #[test]
+    fn default_follows_icons() {
+        assert_eq!(Border::for_icon_set(IconSet::Unicode), Border::Single);
+        assert_eq!(Border::for_icon_set(IconSet::Ascii), Border::Ascii);
+    }
+
+    #[test]
+    fn no_border() {
+        let mut table = Table::new();
+        table
+            .headers(vec!["key", "value"])
+            .row(vec!["a", "1"])
+            .border(Border::None);
+
+        assert_eq!(plain(&table), "key  value\na    1");
+    }
+
+    #[test]
+    fn styled_and_wide_cells() {
+        let mut table = Table::new();
+        table
+            .row(vec!["<green>日本</>", "<bold>x</>"])
+            .row(vec!["abc", "yz"])
+            .border(Border::Rounded);
+
+        let rendered = table.to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(lines[0], "╭──────┬────╮");
+        assert_eq!(lines[1], "│ \x1B[32m日本\x1B[0m │ \x1B[1mx\x1B[0m  │");
+        assert_eq!(lines[2], "│ abc  │ yz │");
+        assert_eq!(lines[3], "╰──────┴────╯");
+    }
+
+    #[test]
+    fn empty_table() {
+        let _icons = pin_icon_set(IconSet::Unicode);
+        assert_eq!(Table::new().to_string(), "┌┐\n└┘");
+        assert_eq!(Table::new().border(Border::None).to_string(), "");
+    }
+}
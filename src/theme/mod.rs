@@ -0,0 +1,462 @@
+//! Custom styles and level templates loaded from a file, so
+//! a palette can be shared and changed without recompiling.
+//!
+//! The format is as simple as it gets, every line outside of a
+//! section is a style made out of other keys. Templates for the
+//! levels go in a `[levels]` section. Quoting works the way it does in
+//! TOML, but since values can be left bare this isn't TOML, which is
+//! why the file is called `paris.theme`:
+//!
+//! * Keys and values can be bare or quoted, with `"basic"` strings
+//!   taking the usual escapes (`\"`, `\\`, `\n`, `\u00E9`, ...) and
+//!   `'literal'` strings taking everything as is
+//! * Styles can be arrays of keys, `["red", "bold"]`
+//! * `#` starts a comment anywhere outside of quotes, so a bare
+//!   value that needs a `#` has to be quoted
+//!
+//! Multi-line strings, dotted keys and any other sections
+//! are rejected with a [`ThemeError`](struct.ThemeError.html).
+//!
+//! ```text
+//! # Shared palette
+//! error-base = red bold
+//! error-strong = ["error-base", "underline"]
+//!
+//! [levels]
+//! info = "<blue>[INFO]</> {}"
+//! error = "<error-strong><cross></> {}"
+//! ```
+use crate::Level;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{env, fs, io};
+
+/// Custom styles and level templates, see the module docs for the format
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Theme {
+    styles: Vec<(String, Vec<String>)>,
+    templates: Vec<(Level, String)>,
+}
+
+impl Theme {
+    /// Reads and parses the theme at the given path
+    ///
+    /// # Example
+    /// ```no_run
+    /// use paris::{Logger, Theme};
+    ///
+    /// let theme = Theme::load("paris.theme").unwrap();
+    /// let mut logger = Logger::new();
+    ///
+    /// logger.theme(&theme);
+    /// ```
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Where the theme for the given app would be,
+    /// `$XDG_CONFIG_HOME/<app>/paris.theme`, or `~/.config/<app>/paris.theme`
+    /// if that isn't set. `None` if there's no file there.
+    ///
+    /// # Example
+    /// ```
+    /// use paris::Theme;
+    ///
+    /// let theme = match Theme::find("my-app") {
+    ///     Some(path) => Theme::load(path).unwrap_or_default(),
+    ///     None => Theme::default(),
+    /// };
+    /// ```
+    pub fn find(app: &str) -> Option<PathBuf> {
+        let config = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+
+        let path = config.join(app).join("paris.theme");
+
+        if path.is_file() {
+            return Some(path);
+        }
+
+        None
+    }
+
+    /// Every style in the theme along with the keys it's made of
+    pub fn styles(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.styles
+            .iter()
+            .map(|(name, colors)| (name.as_str(), colors.as_slice()))
+    }
+
+    /// The template for the given level, if the theme has one
+    pub fn template(&self, level: Level) -> Option<&str> {
+        self.templates
+            .iter()
+            .rev()
+            .find(|(l, _)| *l == level)
+            .map(|(_, template)| template.as_str())
+    }
+}
+
+impl FromStr for Theme {
+    type Err = ThemeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut theme = Theme::default();
+        let mut in_levels = false;
+
+        for (idx, line) in s.lines().enumerate() {
+            let error = |message: &str| ThemeError {
+                line: idx + 1,
+                message: message.to_string(),
+            };
+
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(section) = line.strip_prefix('[') {
+                let (name, rest) = section
+                    .split_once(']')
+                    .ok_or_else(|| error("expected `]` after the section name"))?;
+                end_of_line(rest).map_err(error)?;
+
+                in_levels = match name.trim() {
+                    "styles" => false,
+                    "levels" => true,
+                    _ => return Err(error("unknown section, expected [styles] or [levels]")),
+                };
+
+                continue;
+            }
+
+            let (key, value) = parse_key(line).map_err(error)?;
+
+            if key.is_empty() {
+                return Err(error("missing key"));
+            }
+
+            if in_levels {
+                let level = key
+                    .parse()
+                    .map_err(|_| error("unknown level, expected info, success, warn or error"))?;
+
+                theme
+                    .templates
+                    .push((level, parse_value(value).map_err(error)?));
+                continue;
+            }
+
+            let colors = parse_colors(value).map_err(error)?;
+
+            if colors.is_empty() {
+                return Err(error("style has no colors"));
+            }
+
+            theme.styles.push((key, colors));
+        }
+
+        Ok(theme)
+    }
+}
+
+/// Something in the theme didn't make sense
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThemeError {
+    line: usize,
+    message: String,
+}
+
+impl ThemeError {
+    /// Line the error is on, starting at 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl Display for ThemeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ThemeError {}
+
+/// Splits a line into its key and whatever comes after the `=`
+fn parse_key(line: &str) -> Result<(String, &str), &'static str> {
+    let (key, rest) = match line.chars().next() {
+        Some('"') | Some('\'') => {
+            let (key, rest) = string(line)?;
+            (key, rest.trim_start())
+        }
+        _ => {
+            let end = line.find('=').ok_or("expected `key = value`")?;
+            let key = line[..end].trim();
+
+            if key.contains(['#', '"', '\'', '.']) {
+                return Err("keys with `#`, quotes or dots in them have to be quoted");
+            }
+
+            (key.to_string(), &line[end..])
+        }
+    };
+
+    match rest.strip_prefix('=') {
+        Some(value) => Ok((key, value.trim())),
+        None => Err("expected `key = value`"),
+    }
+}
+
+/// A single value, quoted or not, without any comment after it
+fn parse_value(value: &str) -> Result<String, &'static str> {
+    if value.starts_with(['"', '\'']) {
+        let (value, rest) = string(value)?;
+        end_of_line(rest)?;
+
+        return Ok(value);
+    }
+
+    let value = match value.find('#') {
+        Some(comment) => &value[..comment],
+        None => value,
+    };
+
+    Ok(value.trim().to_string())
+}
+
+/// Splits a style into its keys, either `red bold`,
+/// `"red bold"` or `["red", "bold"]`
+fn parse_colors(value: &str) -> Result<Vec<String>, &'static str> {
+    let mut rest = match value.strip_prefix('[') {
+        Some(list) => list,
+        None => {
+            let value = parse_value(value)?;
+            return Ok(value.split_whitespace().map(String::from).collect());
+        }
+    };
+
+    let mut colors = vec![];
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+
+        if let Some(after) = rest.strip_prefix(']') {
+            end_of_line(after)?;
+            break;
+        }
+
+        if rest.is_empty() || rest.starts_with('#') {
+            return Err("expected `]` at the end of the array");
+        }
+
+        let (color, after) = if rest.starts_with(['"', '\'']) {
+            string(rest)?
+        } else {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == ',' || c == ']' || c == '#')
+                .unwrap_or(rest.len());
+
+            (rest[..end].to_string(), &rest[end..])
+        };
+
+        colors.extend(color.split_whitespace().map(String::from));
+        rest = after;
+    }
+
+    Ok(colors)
+}
+
+/// Reads the quoted string `input` starts with, returning
+/// its contents and everything after the closing quote
+fn string(input: &str) -> Result<(String, &str), &'static str> {
+    if input.starts_with("\"\"\"") || input.starts_with("'''") {
+        return Err("multi-line strings aren't supported");
+    }
+
+    if let Some(literal) = input.strip_prefix('\'') {
+        let end = literal.find('\'').ok_or("missing closing quote")?;
+        return Ok((literal[..end].to_string(), &literal[end + 1..]));
+    }
+
+    let mut value = String::new();
+    let mut chars = input[1..].char_indices();
+
+    while let Some((at, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, &input[at + 2..])),
+            '\\' => value.push(escape(&mut chars)?),
+            _ => value.push(c),
+        }
+    }
+
+    Err("missing closing quote")
+}
+
+/// What the escape sequence in a basic string stands for,
+/// the backslash already taken out of `chars`
+fn escape(chars: &mut std::str::CharIndices) -> Result<char, &'static str> {
+    let digits = match chars.next() {
+        Some((_, 'b')) => return Ok('\u{8}'),
+        Some((_, 't')) => return Ok('\t'),
+        Some((_, 'n')) => return Ok('\n'),
+        Some((_, 'f')) => return Ok('\u{c}'),
+        Some((_, 'r')) => return Ok('\r'),
+        Some((_, '"')) => return Ok('"'),
+        Some((_, '\\')) => return Ok('\\'),
+        Some((_, 'u')) => 4,
+        Some((_, 'U')) => 8,
+        _ => return Err("unknown escape in string"),
+    };
+
+    let code: String = chars.take(digits).map(|(_, c)| c).collect();
+
+    if code.len() != digits {
+        return Err("invalid unicode escape in string");
+    }
+
+    u32::from_str_radix(&code, 16)
+        .ok()
+        .and_then(std::char::from_u32)
+        .ok_or("invalid unicode escape in string")
+}
+
+/// Makes sure there's nothing but a comment left on the line
+fn end_of_line(rest: &str) -> Result<(), &'static str> {
+    let rest = rest.trim_start();
+
+    if rest.is_empty() || rest.starts_with('#') {
+        return Ok(());
+    }
+
+    Err("unexpected text after the value")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THEME: &str = r#"
+        # Shared palette
+        error-base = red bold
+        "error-strong" = ["error-base", "underline"]
+        quiet = 'dimmed'
+
+        [levels]
+        info = "<blue>[INFO]</> {}"
+        warning = <yellow>!</> {}
+
+        [styles]
+        loud = bold
+    "#;
+
+    #[test]
+    fn parse() {
+        let theme: Theme = THEME.parse().unwrap();
+        let styles: Vec<_> = theme.styles().collect();
+
+        assert_eq!(
+            styles,
+            vec![
+                (
+                    "error-base",
+                    &[String::from("red"), String::from("bold")][..]
+                ),
+                (
+                    "error-strong",
+                    &[String::from("error-base"), String::from("underline")][..]
+                ),
+                ("quiet", &[String::from("dimmed")][..]),
+                ("loud", &[String::from("bold")][..]),
+            ]
+        );
+
+        assert_eq!(theme.template(Level::Info), Some("<blue>[INFO]</> {}"));
+        assert_eq!(theme.template(Level::Warn), Some("<yellow>!</> {}"));
+        assert_eq!(theme.template(Level::Error), None);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |input: &str| input.parse::<Theme>().unwrap_err().to_string();
+
+        assert_eq!(error("red"), "line 1: expected `key = value`");
+        assert_eq!(
+            error("\n[colors]"),
+            "line 2: unknown section, expected [styles] or [levels]"
+        );
+        assert_eq!(error("a = []"), "line 1: style has no colors");
+        assert_eq!(
+            error("[levels]\ndebug = {}"),
+            "line 2: unknown level, expected info, success, warn or error"
+        );
+        assert_eq!(
+            error("[levels]\ninfo = \"a\" b"),
+            "line 2: unexpected text after the value"
+        );
+        assert_eq!(error("a = \"red"), "line 1: missing closing quote");
+        assert_eq!(error("a = \"\\q\""), "line 1: unknown escape in string");
+        assert_eq!(
+            error("a = \"\"\"red\"\"\""),
+            "line 1: multi-line strings aren't supported"
+        );
+        assert_eq!(
+            error("a = [red"),
+            "line 1: expected `]` at the end of the array"
+        );
+        assert_eq!(
+            error("a.b = red"),
+            "line 1: keys with `#`, quotes or dots in them have to be quoted"
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let theme: Theme = "[levels] # templates\n\
+            info = \"<cyan>x</> {}\" # note\n\
+            warn = <yellow>!</> {} # bare\n\
+            [styles]\n\
+            a = [\"red\", 'bold'] # array\n\
+            b = green # bare"
+            .parse()
+            .unwrap();
+        let styles: Vec<_> = theme.styles().collect();
+
+        assert_eq!(theme.template(Level::Info), Some("<cyan>x</> {}"));
+        assert_eq!(theme.template(Level::Warn), Some("<yellow>!</> {}"));
+        assert_eq!(
+            styles,
+            vec![
+                ("a", &[String::from("red"), String::from("bold")][..]),
+                ("b", &[String::from("green")][..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        let theme: Theme = r#"
+            "quoted = key" = 'red'
+            [levels]
+            info = "\"{}\" \u00E9\t#"
+            warn = '\n stays # as is'
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(theme.styles().next().unwrap().0, "quoted = key");
+        assert_eq!(theme.template(Level::Info), Some("\"{}\" é\t#"));
+        assert_eq!(theme.template(Level::Warn), Some("\\n stays # as is"));
+    }
+
+    #[test]
+    fn missing_file() {
+        assert!(Theme::load("/definitely/not/here/paris.theme").is_err());
+    }
+}
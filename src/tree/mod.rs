@@ -0,0 +1,188 @@
+//! Trees for things that nest, like dependencies or directories
+//!
+//! # Example
+//! ```
+//! use paris::Tree;
+//!
+//! let mut serde = Tree::new("serde <dimmed>1.0</>");
+//! serde.leaf("serde_derive <dimmed>1.0</>");
+//!
+//! let mut tree = Tree::new("<bold>my-app</>");
+//! tree.child(serde).leaf("paris <dimmed>1.5</>");
+//!
+//! println!("{}", tree);
+//! ```
+use crate::formatter::{self, icon_set, width, IconSet};
+use std::fmt::{self, Display, Formatter};
+
+/// A label with any amount of trees under it
+#[derive(Clone, Debug)]
+pub struct Tree {
+    label: String,
+    children: Vec<Tree>,
+    icon_set: Option<IconSet>,
+}
+
+impl Tree {
+    /// A tree with only a label, which can have keys and several lines
+    pub fn new<T: Display>(label: T) -> Self {
+        Self {
+            label: label.to_string(),
+            children: vec![],
+            icon_set: None,
+        }
+    }
+
+    /// Adds a tree under this one
+    pub fn child(&mut self, tree: Tree) -> &mut Self {
+        self.children.push(tree);
+        self
+    }
+
+    /// Adds a label with nothing under it
+    pub fn leaf<T: Display>(&mut self, label: T) -> &mut Self {
+        self.child(Tree::new(label))
+    }
+
+    /// Draws the connectors with the given set, `├──` or `|--`.
+    /// Follows the icon set everything else uses if not set.
+    pub fn icon_set(&mut self, set: IconSet) -> &mut Self {
+        self.icon_set = Some(set);
+        self
+    }
+
+    /// Every line of the tree, with keys replaced by `colorize`
+    pub(crate) fn lines<F>(&self, mut colorize: F) -> Vec<String>
+    where
+        F: FnMut(&str) -> String,
+    {
+        let connectors = match self.icon_set.unwrap_or_else(icon_set) {
+            IconSet::Unicode => ["├── ", "└── ", "│   ", "    "],
+            IconSet::Ascii => ["|-- ", "`-- ", "|   ", "    "],
+        };
+
+        let mut lines = Self::label_lines(&self.label, &mut colorize);
+        self.children_lines("", &connectors, &mut colorize, &mut lines);
+        lines
+    }
+
+    /// Lines of everything under this tree, each starting with the prefix
+    fn children_lines<F>(
+        &self,
+        prefix: &str,
+        connectors: &[&str; 4],
+        colorize: &mut F,
+        lines: &mut Vec<String>,
+    ) where
+        F: FnMut(&str) -> String,
+    {
+        let [branch, last_branch, pipe, space] = connectors;
+
+        for (idx, child) in self.children.iter().enumerate() {
+            let last = idx == self.children.len() - 1;
+            let (first, rest) = if last {
+                (last_branch, space)
+            } else {
+                (branch, pipe)
+            };
+
+            for (idx, line) in Self::label_lines(&child.label, colorize)
+                .into_iter()
+                .enumerate()
+            {
+                let connector = if idx == 0 { first } else { rest };
+                lines.push(format!("{}{}{}", prefix, connector, line));
+            }
+
+            child.children_lines(&format!("{}{}", prefix, rest), connectors, colorize, lines);
+        }
+    }
+
+    /// The label split up into lines, keeping styles on across them
+    fn label_lines<F>(label: &str, colorize: &mut F) -> Vec<String>
+    where
+        F: FnMut(&str) -> String,
+    {
+        width::wrap(&colorize(label), usize::MAX, 0)
+            .split('\n')
+            .map(String::from)
+            .collect()
+    }
+}
+
+impl Display for Tree {
+    /// Writes the tree with keys replaced, without custom styles.
+    /// Use `Logger::tree` for those.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let lines = self.lines(|text| formatter::colorize_string(text));
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(tree: &Tree) -> String {
+        tree.lines(|text| formatter::format_string(text, false))
+            .join("\n")
+    }
+
+    fn example() -> Tree {
+        let mut serde = Tree::new("serde");
+        serde.leaf("<red>serde_derive</>").leaf("proc-macro2");
+
+        let mut tree = Tree::new("app");
+        tree.child(serde).leaf("paris\nv1.5");
+        tree
+    }
+
+    #[test]
+    fn unicode_tree() {
+        let mut tree = example();
+        tree.icon_set(IconSet::Unicode);
+
+        assert_eq!(
+            plain(&tree),
+            "app\n\
+             ├── serde\n\
+             │   ├── serde_derive\n\
+             │   └── proc-macro2\n\
+             └── paris\n\
+             \x20   v1.5"
+        );
+    }
+
+    #[test]
+    fn ascii_tree() {
+        let mut tree = example();
+        tree.icon_set(IconSet::Ascii);
+
+        assert_eq!(
+            plain(&tree),
+            "app\n\
+             |-- serde\n\
+             |   |-- serde_derive\n\
+             |   `-- proc-macro2\n\
+             `-- paris\n\
+             \x20   v1.5"
+        );
+    }
+
+    #[test]
+    fn styled_labels() {
+        let mut tree = Tree::new("<green>a\nb</>");
+        tree.icon_set(IconSet::Unicode).leaf("<bold>c</>");
+
+        assert_eq!(
+            tree.to_string(),
+            "\x1B[32ma\x1B[0m\n\x1B[32mb\x1B[0m\n└── \x1B[1mc\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn lonely_root() {
+        assert_eq!(plain(&Tree::new("alone")), "alone");
+    }
+}